@@ -8,4 +8,5 @@ cargo check --quiet --workspace --all-features --lib --target wasm32-unknown-unk
 cargo clippy --quiet --workspace --all-targets --all-features --  -D warnings -W clippy::all
 cargo test --quiet --workspace --all-targets --all-features
 cargo test --quiet --workspace --doc
+cargo run --quiet -- check
 trunk build
@@ -0,0 +1,296 @@
+//! Resume content model, loaded from a TOML file instead of being hardcoded in the page functions.
+
+use serde::Deserialize;
+
+use crate::markdown::{split_wiki_links, Piece};
+use crate::pages::Page;
+
+/// The resume that ships with the site.
+pub const DEFAULT_CONTENT: &str = include_str!("../assets/resume.toml");
+
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Content {
+    pub person: Person,
+    /// Shown at the bottom of the home page.
+    #[serde(default)]
+    pub updated: Option<String>,
+    /// Keeps visitors from closing the last open tab, instead of showing the site tree once they do.
+    #[serde(default)]
+    pub keep_last_tab: bool,
+    /// The "Explore" tree on the home page.
+    #[serde(default)]
+    pub tree: Vec<TreeEntry>,
+    #[serde(default)]
+    pub pages: Vec<PageContent>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub jobs: Vec<Job>,
+    #[serde(default)]
+    pub schools: Vec<School>,
+}
+
+impl Content {
+    pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    pub fn page(&self, page: &Page) -> Option<&PageContent> {
+        self.pages.iter().find(|p| &p.id == page)
+    }
+
+    pub fn title<'a>(&'a self, page: &'a Page) -> &'a str {
+        if let Some(project) = page.project_id().and_then(|id| self.project(id)) {
+            return &project.title;
+        }
+        self.page(page).map_or(page.id(), |p| p.title.as_str())
+    }
+
+    pub fn project(&self, id: &str) -> Option<&Project> {
+        self.projects.iter().find(|p| p.id == id)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Person {
+    pub name: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub links: Vec<ExternalLink>,
+    #[serde(default)]
+    pub photo: Option<Image>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ExternalLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Image {
+    pub url: String,
+    /// Hover text.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Weak text shown below the image.
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TreeEntry {
+    pub page: Page,
+    #[serde(default)]
+    pub level: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PageContent {
+    pub id: Page,
+    pub title: String,
+    /// Header text, defaults to the title.
+    #[serde(default)]
+    pub heading: Option<String>,
+    #[serde(default)]
+    pub kind: PageKind,
+    /// Weak hint shown above the sections.
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+    /// Links in the "Related:" footer, instead of the pages linked to and from this one.
+    #[serde(default)]
+    pub related: Option<Vec<Page>>,
+}
+
+impl PageContent {
+    /// The target of every `[[target|label]]` link on the page.
+    pub fn wiki_links(&self) -> Vec<&str> {
+        let mut links = vec![];
+        for section in &self.sections {
+            let spans = section.text.iter().flat_map(|paragraph| match paragraph {
+                Paragraph::Plain(text) => vec![text.as_str()],
+                Paragraph::Spans(spans) => spans.iter().map(Span::text).collect(),
+                Paragraph::Styled(span) => vec![span.text()],
+            });
+            for text in spans.chain(section.markdown.as_deref()) {
+                for piece in split_wiki_links(text) {
+                    if let Piece::WikiLink { target, .. } = piece {
+                        links.push(target);
+                    }
+                }
+            }
+        }
+        links
+    }
+}
+
+/// Selects which records are rendered after the page's sections.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PageKind {
+    #[default]
+    Text,
+    Home,
+    Contact,
+    Portfolio,
+    WorkHistory,
+    Education,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Section {
+    #[serde(default)]
+    pub heading: Option<String>,
+    /// Turns the heading into an internal link.
+    #[serde(default)]
+    pub link: Option<Page>,
+    /// Draws the section inside a group frame.
+    #[serde(default)]
+    pub boxed: bool,
+    #[serde(default)]
+    pub text: Vec<Paragraph>,
+    /// CommonMark shown after the text paragraphs.
+    #[serde(default)]
+    pub markdown: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Paragraph {
+    Plain(String),
+    Spans(Vec<Span>),
+    Styled(Span),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Span {
+    Plain(String),
+    Styled {
+        text: String,
+        #[serde(default)]
+        style: TextStyle,
+    },
+}
+
+impl Span {
+    pub fn text(&self) -> &str {
+        match self {
+            Span::Plain(text) | Span::Styled { text, .. } => text,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextStyle {
+    #[default]
+    Normal,
+    Weak,
+    Strong,
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// A portfolio entry, shown on its own page at [`Page::project`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub title: String,
+    /// One-line description for the portfolio.
+    pub summary: String,
+    pub role: String,
+    pub status: Status,
+    pub overview: String,
+    #[serde(default)]
+    pub dates: Option<String>,
+    #[serde(default)]
+    pub tech: Vec<String>,
+    #[serde(default)]
+    pub links: Vec<ExternalLink>,
+    #[serde(default)]
+    pub gallery: Vec<Image>,
+    /// Links in the "Related:" footer, instead of the pages linked to and from this one.
+    #[serde(default)]
+    pub related: Option<Vec<Page>>,
+    /// Unlisted projects still have a page, but no portfolio entry.
+    #[serde(default = "listed")]
+    pub listed: bool,
+}
+
+fn listed() -> bool {
+    true
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    InProgress,
+    UnderReview,
+    Complete,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Job {
+    pub title: String,
+    pub organization: String,
+    pub dates: String,
+    #[serde(default)]
+    pub duties: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct School {
+    pub name: String,
+    pub degree: String,
+    pub dates: String,
+    #[serde(default)]
+    pub gpa: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_content_parses() {
+        let content = Content::from_toml(DEFAULT_CONTENT).expect("default content parses");
+        assert!(!content.person.name.is_empty());
+        assert!(!content.pages.is_empty());
+    }
+
+    #[test]
+    fn content_needs_a_person() {
+        assert!(Content::from_toml("").is_err());
+        assert!(Content::from_toml("[person]\nname = \"Ada\"").is_ok());
+    }
+
+    #[test]
+    fn paragraphs_are_plain_spans_or_styled() {
+        #[derive(Deserialize)]
+        struct Text {
+            text: Vec<Paragraph>,
+        }
+
+        let text: Text = toml::from_str(r#"
+            text = ["plain", ["a ", { text = "b", style = "weak" }], { text = "c", style = "strong" }]
+        "#).expect("paragraphs parse");
+        let [plain, spans, styled] = &text.text[..] else {
+            panic!("three paragraphs: {:?}", text.text);
+        };
+        assert!(matches!(plain, Paragraph::Plain(text) if text == "plain"));
+        let Paragraph::Spans(spans) = spans else {
+            panic!("spans: {spans:?}");
+        };
+        assert!(matches!(&spans[0], Span::Plain(text) if text == "a "));
+        assert!(matches!(&spans[1], Span::Styled { text, style: TextStyle::Weak, .. } if text == "b"));
+        assert!(matches!(styled, Paragraph::Styled(Span::Styled { style: TextStyle::Strong, .. })));
+    }
+}
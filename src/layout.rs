@@ -0,0 +1,659 @@
+//! Dock layouts, and saving them between sessions.
+
+#[cfg(any(target_arch = "wasm32", test))]
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use egui_dock::{DockState, Node, NodeIndex, Surface, Tree};
+#[cfg(any(target_arch = "wasm32", test))]
+use egui_dock::{Split, TabIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::pages::{Page, Registry, Tab};
+
+/// Bump when saved layouts can no longer be restored as they are.
+pub const LAYOUT_VERSION: u32 = 3;
+
+const LAYOUT_KEY: &str = "layout";
+
+#[derive(Deserialize, Serialize)]
+pub struct SavedLayout {
+    version: u32,
+    pub dock: DockState<Tab>,
+    #[serde(default)]
+    pub undocked: Vec<Undocked>,
+    #[serde(default)]
+    pub pinned: Vec<Tab>,
+    #[serde(default)]
+    pub groups: Vec<TabGroup>,
+}
+
+/// Open tabs saved under a name, to be restored with one click.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TabGroup {
+    pub name: String,
+    pub dock: DockState<Tab>,
+    /// The tabs that were in their own OS windows.
+    #[serde(default)]
+    pub undocked: Vec<Undocked>,
+}
+
+/// A tab shown in its own OS window on the native build, outside of the dock.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Undocked {
+    pub tab: Tab,
+    /// Where the window was last, as the outer position that [`egui::ViewportBuilder`] places new
+    /// windows at.
+    #[serde(default)]
+    pub position: Option<egui::Pos2>,
+    /// The last inner size of the window, also as the builder takes it.
+    #[serde(default)]
+    pub size: Option<egui::Vec2>,
+    /// Whether the window was created in this session, after which the OS places it.
+    #[serde(skip)]
+    pub created: bool,
+}
+
+impl Undocked {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(tab: Tab) -> Self {
+        Self { tab, position: None, size: None, created: false }
+    }
+}
+
+/// A range of window widths with its own default layout.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Breakpoint {
+    Phone,
+    Tablet,
+    Desktop,
+}
+
+impl Breakpoint {
+    pub fn of_width(width: f32) -> Self {
+        if width < 600.0 {
+            Breakpoint::Phone
+        } else if width < 1000.0 {
+            Breakpoint::Tablet
+        } else {
+            Breakpoint::Desktop
+        }
+    }
+}
+
+/// Home alone on phones. Next to it, Contact and Help share a column on tablets, and are stacked
+/// on desktops. Pages missing from `pages` are left out.
+pub fn default_layout(breakpoint: Breakpoint, pages: &Registry) -> DockState<Tab> {
+    let mut dock = DockState::new(vec![Page::home().into()]);
+    match breakpoint {
+        Breakpoint::Phone => {}
+        Breakpoint::Tablet => {
+            let [_a, _b] = dock.main_surface_mut()
+                .split_left(NodeIndex::root(), 0.4, vec![Page::new("contact").into(), Page::new("help").into()]);
+        }
+        Breakpoint::Desktop => {
+            let [_a, b] = dock.main_surface_mut()
+                .split_left(NodeIndex::root(), 0.3, vec![Page::new("contact").into()]);
+            let [_a, _b] = dock.main_surface_mut()
+                .split_below(b, 0.5, vec![Page::new("help").into()]);
+        }
+    }
+    registered(dock, pages)
+}
+
+/// Drops the tabs of pages other content files may not have from a built-in layout.
+fn registered(mut dock: DockState<Tab>, pages: &Registry) -> DockState<Tab> {
+    // `DockState::retain_tabs` can leave nodes under the wrong parent, so close them one by one.
+    let unknown: Vec<Tab> = dock.iter_all_tabs().map(|(_, tab)| tab)
+        .filter(|tab| !pages.contains(&tab.page))
+        .cloned()
+        .collect();
+    for tab in unknown {
+        if let Some(ids) = dock.find_tab(&tab) {
+            dock.remove_tab(ids);
+        }
+    }
+    dock
+}
+
+/// The default layout of `to` to swap in, if `dock` is the default layout of `from`. Layouts
+/// arranged by the visitor, including any floating windows, are kept as they are.
+pub fn rebalance(
+    dock: &DockState<Tab>,
+    pages: &Registry,
+    from: Breakpoint,
+    to: Breakpoint,
+) -> Option<DockState<Tab>> {
+    let floating = dock.iter_surfaces().any(|surface| matches!(surface, Surface::Window(..)));
+    let swap = !floating && encode_layout(dock) == encode_layout(&default_layout(from, pages));
+    swap.then(|| default_layout(to, pages))
+}
+
+/// A named layout offered in the top bar.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Preset {
+    /// The default layout.
+    Overview,
+    /// Contact and Skills stacked beside the Portfolio.
+    Recruiter,
+    /// Only the focused page.
+    Reader,
+    /// The Portfolio beside all of its projects.
+    DeepDive,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Overview, Preset::Recruiter, Preset::Reader, Preset::DeepDive];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Overview => "Overview",
+            Preset::Recruiter => "Recruiter",
+            Preset::Reader => "Reader",
+            Preset::DeepDive => "Deep dive",
+        }
+    }
+
+    /// The layout with `focused` as the page to read and `projects` to dive into, leaving out
+    /// pages missing from `pages`.
+    pub fn layout(self, pages: &Registry, focused: Page, projects: Vec<Page>) -> DockState<Tab> {
+        let dock = match self {
+            Preset::Overview => default_layout(Breakpoint::Desktop, pages),
+            Preset::Recruiter => {
+                let mut dock = DockState::new(vec![Page::new("portfolio").into()]);
+                let [_a, b] = dock.main_surface_mut()
+                    .split_left(NodeIndex::root(), 0.4, vec![Page::new("contact").into()]);
+                let [_a, _b] = dock.main_surface_mut()
+                    .split_below(b, 0.5, vec![Page::new("skills").into()]);
+                dock
+            }
+            Preset::Reader => DockState::new(vec![focused.into()]),
+            Preset::DeepDive if projects.is_empty() => DockState::new(vec![Page::new("portfolio").into()]),
+            Preset::DeepDive => {
+                let mut dock = DockState::new(vec![Page::new("portfolio").into()]);
+                let [_a, _b] = dock.main_surface_mut()
+                    .split_right(NodeIndex::root(), 0.35, projects.into_iter().map(Tab::from).collect());
+                dock
+            }
+        };
+        registered(dock, pages)
+    }
+}
+
+/// The layout of the last session, unless it's from another version or shows unknown pages.
+pub fn load_layout(storage: Option<&dyn eframe::Storage>, pages: &Registry) -> Option<SavedLayout> {
+    let mut saved: SavedLayout = eframe::get_value(storage?, LAYOUT_KEY)?;
+    if saved.version != LAYOUT_VERSION {
+        log::warn!("discarding saved layout of version {}", saved.version);
+        return None;
+    }
+    let unknown = saved.dock.iter_all_tabs().map(|(_, tab)| &tab.page)
+        .chain(saved.undocked.iter().map(|undocked| &undocked.tab.page))
+        .find(|page| !pages.contains(page));
+    if let Some(page) = unknown {
+        log::warn!("discarding saved layout showing unknown page `{}`", page.id());
+        return None;
+    }
+    saved.pinned.retain(|tab| pages.contains(&tab.page));
+    saved.groups.retain(|group| {
+        let unknown = group.dock.iter_all_tabs().map(|(_, tab)| &tab.page)
+            .chain(group.undocked.iter().map(|undocked| &undocked.tab.page))
+            .find(|page| !pages.contains(page));
+        if let Some(page) = unknown {
+            log::warn!("discarding tab group `{}` showing unknown page `{}`", group.name, page.id());
+        }
+        unknown.is_none()
+    });
+    // Floating windows only remember where they were, so move them back there.
+    for surface in saved.dock.iter_surfaces_mut() {
+        if let Surface::Window(_, window) = surface {
+            let rect = window.rect();
+            if rect.is_finite() && rect.is_positive() {
+                window.set_position(rect.min).set_size(rect.size());
+            }
+        }
+    }
+    Some(saved)
+}
+
+impl SavedLayout {
+    pub fn new(
+        dock: DockState<Tab>,
+        undocked: Vec<Undocked>,
+        pinned: Vec<Tab>,
+        groups: Vec<TabGroup>,
+    ) -> Self {
+        Self { version: LAYOUT_VERSION, dock, undocked, pinned, groups }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, LAYOUT_KEY, self);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// Where the web build is deployed, for sharing layouts from the native build.
+#[cfg(not(target_arch = "wasm32"))]
+const SITE_URL: &str = "https://mmnorm.github.io/personal-website/";
+
+fn site_url() -> String {
+    #[cfg(target_arch = "wasm32")]
+    return crate::web::location_without_hash();
+    #[cfg(not(target_arch = "wasm32"))]
+    return SITE_URL.to_owned();
+}
+
+/// A link that opens the site with the layout of `dock`.
+pub fn share_url(dock: &DockState<Tab>) -> String {
+    format!("{}#{LAYOUT_KEY}={}", site_url(), encode_layout(dock))
+}
+
+/// A link that opens the site on `page`.
+pub fn page_url(page: &Page) -> String {
+    format!("{}#/{}", site_url(), page.id())
+}
+
+/// Encodes the main surface as text for URLs, such as `h0.30(contact;v0.50(*home,skills;help))`.
+/// Floating windows are left out.
+///
+/// A leaf lists its page ids with the active one marked by `*`. A split gives its direction (`h`
+/// for side by side, `v` for stacked), the fraction of the first child, and both children. Page
+/// ids are percent-escaped, see [`escape_id`].
+pub fn encode_layout(dock: &DockState<Tab>) -> String {
+    let mut text = String::new();
+    encode_node(dock.main_surface(), NodeIndex::root(), &mut text);
+    text
+}
+
+fn encode_node(tree: &Tree<Tab>, node: NodeIndex, text: &mut String) {
+    let (direction, fraction) = match &tree[node] {
+        Node::Empty => return,
+        Node::Leaf { tabs, active, .. } => {
+            for (i, tab) in tabs.iter().enumerate() {
+                if i > 0 {
+                    text.push(',');
+                }
+                if i == active.0 && tabs.len() > 1 {
+                    text.push('*');
+                }
+                escape_id(tab.page.id(), text);
+            }
+            return;
+        }
+        Node::Horizontal { fraction, .. } => ('h', fraction),
+        Node::Vertical { fraction, .. } => ('v', fraction),
+    };
+    text.push_str(&format!("{direction}{fraction:.2}("));
+    encode_node(tree, node.left(), text);
+    text.push(';');
+    encode_node(tree, node.right(), text);
+    text.push(')');
+}
+
+/// Appends `id` to `text` with every byte but letters, digits and `-._~/` percent-escaped, which
+/// covers the separators of [`encode_layout`] and anything else URLs can't hold.
+fn escape_id(id: &str, text: &mut String) {
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            text.push(byte.into());
+        } else {
+            text.push_str(&format!("%{byte:02X}"));
+        }
+    }
+}
+
+/// Reverses [`escape_id`], or returns `None` for a malformed escape.
+#[cfg(any(target_arch = "wasm32", test))]
+fn unescape_id(id: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut rest = id.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = rest.get(..2).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+        bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(any(target_arch = "wasm32", test))]
+/// How deeply splits of a decoded layout may nest. Every level doubles the nodes egui_dock
+/// allocates, so links from outside mustn't go much deeper than layouts visitors can arrange.
+const MAX_DEPTH: usize = 8;
+
+/// A layout parsed from [`encode_layout`]'s format.
+#[cfg(any(target_arch = "wasm32", test))]
+enum Layout {
+    Leaf { pages: Vec<Page>, active: usize },
+    Split { split: Split, fraction: f32, first: Box<Layout>, second: Box<Layout> },
+}
+
+#[cfg(any(target_arch = "wasm32", test))]
+impl Layout {
+    fn parse(text: &mut &str, depth: usize) -> Option<Self> {
+        let split = match text.chars().next()? {
+            'h' => Split::Right,
+            'v' => Split::Below,
+            _ => return Self::parse_leaf(text),
+        };
+        let Some(open) = text.find('(').filter(|&open| text[1..open].parse::<f32>().is_ok()) else {
+            return Self::parse_leaf(text);
+        };
+        let fraction: f32 = text[1..open].parse().ok()?;
+        if !(0.0..=1.0).contains(&fraction) || depth >= MAX_DEPTH {
+            return None;
+        }
+        *text = &text[open + 1..];
+        let first = Self::parse(text, depth + 1)?;
+        *text = text.strip_prefix(';')?;
+        let second = Self::parse(text, depth + 1)?;
+        *text = text.strip_prefix(')')?;
+        Some(Self::Split { split, fraction, first: Box::new(first), second: Box::new(second) })
+    }
+
+    fn parse_leaf(text: &mut &str) -> Option<Self> {
+        let end = text.find([';', ')']).unwrap_or(text.len());
+        let (leaf, rest) = text.split_at(end);
+        *text = rest;
+        let mut active = 0;
+        let mut pages = vec![];
+        for (i, id) in leaf.split(',').enumerate() {
+            let id = match id.strip_prefix('*') {
+                Some(id) => {
+                    active = i;
+                    id
+                }
+                None => id,
+            };
+            pages.push(Page::new(unescape_id(id)?));
+        }
+        if pages.iter().any(|page| page.id().is_empty()) {
+            return None;
+        }
+        Some(Self::Leaf { pages, active })
+    }
+
+    fn pages(&self) -> Vec<&Page> {
+        match self {
+            Self::Leaf { pages, .. } => pages.iter().collect(),
+            Self::Split { first, second, .. } => [first.pages(), second.pages()].concat(),
+        }
+    }
+
+    fn first_leaf(&self) -> Vec<Tab> {
+        match self {
+            Self::Leaf { pages, .. } => pages.iter().cloned().map(Tab::from).collect(),
+            Self::Split { first, .. } => first.first_leaf(),
+        }
+    }
+
+    /// Splits `node`, which shows the pages of the first leaf, until it matches this layout.
+    fn build(&self, tree: &mut Tree<Tab>, node: NodeIndex) {
+        match self {
+            Self::Leaf { active, .. } => tree.set_active_tab(node, TabIndex(*active)),
+            Self::Split { split, fraction, first, second } => {
+                let [a, b] = tree.split(node, *split, *fraction, Node::leaf_with(second.first_leaf()));
+                first.build(tree, a);
+                second.build(tree, b);
+            }
+        }
+    }
+}
+
+/// Parses a layout from [`encode_layout`], if it's well-formed and only shows known pages.
+#[cfg(any(target_arch = "wasm32", test))]
+pub fn decode_layout(mut text: &str, pages: &Registry) -> Option<DockState<Tab>> {
+    let layout = Layout::parse(&mut text, 0).filter(|_| text.is_empty())?;
+    if let Some(page) = layout.pages().into_iter().find(|page| !pages.contains(page)) {
+        log::warn!("ignoring layout showing unknown page `{}`", page.id());
+        return None;
+    }
+    let mut dock = DockState::new(layout.first_leaf());
+    layout.build(dock.main_surface_mut(), NodeIndex::root());
+    // A page listed more than once opens as that many instances.
+    let mut instances = BTreeMap::<Page, u32>::new();
+    for (_, tab) in dock.iter_all_tabs_mut() {
+        let next = instances.entry(tab.page.clone()).or_default();
+        tab.instance = *next;
+        *next += 1;
+    }
+    Some(dock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{Content, DEFAULT_CONTENT};
+    use crate::pages::PageEntry;
+    use crate::State;
+
+    fn registry() -> Registry {
+        crate::content_pages(Content::from_toml(DEFAULT_CONTENT).expect("default content parses"))
+    }
+
+    fn round_trip(dock: &DockState<Tab>) {
+        let text = encode_layout(dock);
+        let decoded = decode_layout(&text, &registry()).unwrap_or_else(|| panic!("`{text}` decodes"));
+        assert_eq!(encode_layout(&decoded), text);
+        let tabs = |dock: &DockState<Tab>| -> Vec<Tab> {
+            dock.iter_all_tabs().map(|(_, tab)| tab.clone()).collect()
+        };
+        assert_eq!(tabs(&decoded), tabs(dock));
+    }
+
+    #[test]
+    fn default_layouts_round_trip() {
+        for breakpoint in [Breakpoint::Phone, Breakpoint::Tablet, Breakpoint::Desktop] {
+            round_trip(&default_layout(breakpoint, &registry()));
+        }
+    }
+
+    #[test]
+    fn presets_round_trip() {
+        let projects = vec![Page::project("markdown-editor"), Page::project("modular-programming-model")];
+        for preset in Preset::ALL {
+            round_trip(&preset.layout(&registry(), Page::new("skills"), projects.clone()));
+        }
+    }
+
+    #[test]
+    fn duplicate_tabs_round_trip() {
+        let mut dock = default_layout(Breakpoint::Desktop, &registry());
+        dock.push_to_first_leaf(Tab { page: Page::home(), instance: 1 });
+        assert_eq!(encode_layout(&dock), "h0.30(v0.50(contact;help);home,*home)");
+        round_trip(&dock);
+    }
+
+    #[test]
+    fn malformed_layouts_are_rejected() {
+        let pages = registry();
+        let malformed = [
+            "", "h0.5(", "hNaN(home;help)", "h2(home;help)", "h0.5(;help)", "home,",
+            "h0.5(home;help",
+        ];
+        for text in malformed {
+            assert!(decode_layout(text, &pages).is_none(), "`{text}` is rejected");
+        }
+    }
+
+    #[test]
+    fn page_ids_may_start_like_splits() {
+        let dock = decode_layout("v0.50(help;home)", &registry()).expect("layout decodes");
+        assert_eq!(encode_layout(&dock), "v0.50(help;home)");
+        let mut text = "v0.5x";
+        let leaf = Layout::parse(&mut text, 0);
+        assert!(matches!(leaf, Some(Layout::Leaf { pages, .. }) if pages == [Page::new("v0.5x")]));
+        assert!(decode_layout("v0.5x", &registry()).is_none(), "unknown pages are rejected");
+    }
+
+    #[test]
+    fn deep_layouts_are_rejected() {
+        let nest = |depth: usize| "h0.5(home;".repeat(depth) + "help" + &")".repeat(depth);
+        assert!(decode_layout(&nest(MAX_DEPTH), &registry()).is_some());
+        assert!(decode_layout(&nest(MAX_DEPTH + 1), &registry()).is_none());
+        assert!(decode_layout(&nest(30), &registry()).is_none());
+    }
+
+    struct TestPage;
+
+    impl PageEntry for TestPage {
+        fn title(&self) -> String {
+            String::new()
+        }
+
+        fn body(&self, _: &mut egui::Ui, _: &mut State) {}
+    }
+
+    /// A registry without most of the pages of the built-in layouts.
+    fn few_pages() -> Registry {
+        let mut pages = Registry::default();
+        for id in ["home", "help", "portfolio"] {
+            pages.register(Page::new(id), TestPage);
+        }
+        pages
+    }
+
+    #[test]
+    fn default_layouts_leave_out_missing_pages() {
+        let pages = few_pages();
+        assert_eq!(encode_layout(&default_layout(Breakpoint::Tablet, &pages)), "h0.40(help;home)");
+        assert_eq!(encode_layout(&default_layout(Breakpoint::Desktop, &pages)), "h0.30(help;home)");
+    }
+
+    #[test]
+    fn presets_leave_out_missing_pages() {
+        let pages = few_pages();
+        let layouts = Preset::ALL.map(|preset| encode_layout(&preset.layout(&pages, Page::home(), vec![])));
+        assert_eq!(layouts, ["h0.30(help;home)", "portfolio", "home", "portfolio"]);
+    }
+
+    #[test]
+    fn presets_show_registered_pages() {
+        let pages = registry();
+        let projects = vec![Page::project("markdown-editor"), Page::project("eev-data-model")];
+        let layouts = Preset::ALL
+            .map(|preset| encode_layout(&preset.layout(&pages, Page::new("goals"), projects.clone())));
+        assert_eq!(layouts, [
+            "h0.30(v0.50(contact;help);home)",
+            "h0.40(v0.50(contact;skills);portfolio)",
+            "goals",
+            "h0.35(portfolio;*portfolio/markdown-editor,portfolio/eev-data-model)",
+        ]);
+    }
+
+    #[test]
+    fn rebalance_only_swaps_default_layouts() {
+        let pages = registry();
+        let dock = default_layout(Breakpoint::Desktop, &pages);
+        let mut dock = rebalance(&dock, &pages, Breakpoint::Desktop, Breakpoint::Phone)
+            .expect("the default layout is swapped");
+        assert_eq!(encode_layout(&dock), encode_layout(&default_layout(Breakpoint::Phone, &pages)));
+        // Not the default layout of the breakpoint it's coming from.
+        assert!(rebalance(&dock, &pages, Breakpoint::Tablet, Breakpoint::Desktop).is_none());
+
+        dock.push_to_first_leaf(Page::new("skills").into());
+        assert!(rebalance(&dock, &pages, Breakpoint::Phone, Breakpoint::Desktop).is_none());
+
+        let mut dock = default_layout(Breakpoint::Phone, &pages);
+        dock.add_window(vec![Page::new("skills").into()]);
+        assert!(rebalance(&dock, &pages, Breakpoint::Phone, Breakpoint::Desktop).is_none());
+    }
+
+    /// Keeps the saved values in memory.
+    #[derive(Default)]
+    struct MemoryStorage(BTreeMap<String, String>);
+
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_owned(), value);
+        }
+
+        fn flush(&mut self) {}
+    }
+
+    fn saved(layout: SavedLayout) -> MemoryStorage {
+        let mut storage = MemoryStorage::default();
+        layout.save(&mut storage);
+        storage
+    }
+
+    #[test]
+    fn saved_layouts_load() {
+        let pages = registry();
+        let dock = default_layout(Breakpoint::Tablet, &pages);
+        let storage = saved(SavedLayout::new(dock.clone(), vec![], vec![], vec![]));
+        let loaded = load_layout(Some(&storage), &pages).expect("the layout loads");
+        assert_eq!(encode_layout(&loaded.dock), encode_layout(&dock));
+        assert!(load_layout(None, &pages).is_none());
+        assert!(load_layout(Some(&MemoryStorage::default()), &pages).is_none());
+    }
+
+    #[test]
+    fn layouts_of_other_versions_are_discarded() {
+        let pages = registry();
+        let mut layout = SavedLayout::new(default_layout(Breakpoint::Tablet, &pages), vec![], vec![], vec![]);
+        layout.version = LAYOUT_VERSION - 1;
+        assert!(load_layout(Some(&saved(layout)), &pages).is_none());
+    }
+
+    #[test]
+    fn unknown_pages_are_discarded() {
+        let pages = registry();
+        let unknown = Tab::from(Page::new("no-such-page"));
+        let dock = DockState::new(vec![Page::home().into(), unknown.clone()]);
+        let layout = SavedLayout::new(dock, vec![], vec![], vec![]);
+        assert!(load_layout(Some(&saved(layout)), &pages).is_none(), "unknown docked page");
+
+        let home = DockState::new(vec![Page::home().into()]);
+        let undocked = vec![Undocked::new(unknown.clone())];
+        let layout = SavedLayout::new(home.clone(), undocked, vec![], vec![]);
+        assert!(load_layout(Some(&saved(layout)), &pages).is_none(), "unknown undocked page");
+
+        let group = |name: &str, dock| TabGroup { name: name.to_owned(), dock, undocked: vec![] };
+        let mut undocked = group("undocked", home.clone());
+        undocked.undocked.push(Undocked::new(unknown.clone()));
+        let groups = vec![
+            group("unknown", DockState::new(vec![unknown.clone()])),
+            group("home", home.clone()),
+            undocked,
+        ];
+        let layout = SavedLayout::new(home, vec![], vec![Page::home().into(), unknown], groups);
+        let loaded = load_layout(Some(&saved(layout)), &pages).expect("the layout loads");
+        assert_eq!(loaded.pinned, [Tab::from(Page::home())]);
+        assert_eq!(loaded.groups.iter().map(|group| &group.name[..]).collect::<Vec<_>>(), ["home"]);
+    }
+
+    #[test]
+    fn odd_page_ids_round_trip() {
+        let mut pages = few_pages();
+        let odd = Page::new("notes, part (1); *draft* 100%");
+        pages.register(odd.clone(), TestPage);
+        let mut dock = DockState::new(vec![Page::home().into(), odd.clone().into()]);
+        dock.main_surface_mut().split_below(NodeIndex::root(), 0.5, vec![odd.clone().into()]);
+        let text = encode_layout(&dock);
+        let escaped = "notes%2C%20part%20%281%29%3B%20%2Adraft%2A%20100%25";
+        assert_eq!(text, format!("v0.50(*home,{escaped};{escaped})"));
+        let decoded = decode_layout(&text, &pages).expect("the layout decodes");
+        assert_eq!(encode_layout(&decoded), text);
+        let tabs: Vec<Tab> = decoded.iter_all_tabs().map(|(_, tab)| tab.clone()).collect();
+        assert_eq!(tabs, [Tab::from(Page::home()), Tab::from(odd.clone()), Tab { page: odd, instance: 1 }]);
+    }
+
+    #[test]
+    fn malformed_escapes_are_rejected() {
+        let pages = registry();
+        assert!(decode_layout("h%6Fme", &pages).is_some());
+        for text in ["home%", "home%2", "home%zz", "home%+1", "%FFhome"] {
+            assert!(decode_layout(text, &pages).is_none(), "`{text}` is rejected");
+        }
+    }
+}
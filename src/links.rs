@@ -0,0 +1,229 @@
+//! The graph of internal links between pages.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+
+use crate::pages::{link_key, Page, Registry};
+
+/// Every internal link of every registered page, resolved to the page it opens.
+#[derive(Default)]
+pub struct LinkGraph {
+    outgoing: BTreeMap<Page, Vec<Page>>,
+    backlinks: BTreeMap<Page, Vec<Page>>,
+    /// Links whose target isn't a registered page.
+    dead: Vec<(Page, String)>,
+}
+
+impl LinkGraph {
+    pub fn new(pages: &Registry, targets: &BTreeMap<String, Page>) -> Self {
+        let mut graph = Self::default();
+        for page in pages.pages() {
+            let entry = pages.get(page).expect("listed pages are registered");
+            let links = entry.links().into_iter()
+                .map(|target| (targets.get(&link_key(&target)).cloned(), target));
+            // Footer overrides link to pages by id.
+            let related = entry.related().unwrap_or_default().into_iter()
+                .map(|to| (pages.contains(&to).then(|| to.clone()), to.id().to_owned()));
+            for (to, target) in links.chain(related) {
+                let Some(to) = to else {
+                    graph.dead.push((page.clone(), target));
+                    continue;
+                };
+                let outgoing = graph.outgoing.entry(page.clone()).or_default();
+                if !outgoing.contains(&to) {
+                    outgoing.push(to.clone());
+                    graph.backlinks.entry(to).or_default().push(page.clone());
+                }
+            }
+        }
+        graph
+    }
+
+    pub fn outgoing(&self, page: &Page) -> &[Page] {
+        self.outgoing.get(page).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn backlinks(&self, page: &Page) -> &[Page] {
+        self.backlinks.get(page).map_or(&[], Vec::as_slice)
+    }
+
+    /// The pages `page` links to, followed by the pages linking to it.
+    pub fn related(&self, page: &Page) -> Vec<Page> {
+        let mut related: Vec<Page> = vec![];
+        for other in self.outgoing(page).iter().chain(self.backlinks(page)) {
+            if other != page && !related.contains(other) {
+                related.push(other.clone());
+            }
+        }
+        related
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// A problem found by [`LinkGraph::validate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    DeadLink { page: Page, target: String },
+    /// No chain of links leads from the home page to the page.
+    Unreachable(Page),
+    /// No page links to the page.
+    Orphan(Page),
+    NotInTree(Page),
+    NoFooterLinks(Page),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::DeadLink { page, target } => {
+                write!(f, "dead link: `{}` links to unknown page `{target}`", page.id())
+            }
+            Problem::Unreachable(page) => {
+                write!(f, "unreachable: `{}` can't be reached from `{}`", page.id(), Page::home().id())
+            }
+            Problem::Orphan(page) => write!(f, "orphan: no page links to `{}`", page.id()),
+            Problem::NotInTree(page) => write!(f, "not in tree: `{}` is missing from the home tree", page.id()),
+            Problem::NoFooterLinks(page) => write!(f, "no footer links: `{}` has no related pages", page.id()),
+        }
+    }
+}
+
+impl LinkGraph {
+    /// Walks the links from the home page, reporting every page that visitors can't navigate to.
+    pub fn validate(&self, pages: &Registry, tree: &[Page]) -> Vec<Problem> {
+        let mut problems: Vec<Problem> = self.dead.iter()
+            .map(|(page, target)| Problem::DeadLink { page: page.clone(), target: target.clone() })
+            .collect();
+
+        let mut reachable = BTreeSet::from([Page::home()]);
+        let mut queue = VecDeque::from([Page::home()]);
+        while let Some(page) = queue.pop_front() {
+            for to in self.outgoing(&page) {
+                if reachable.insert(to.clone()) {
+                    queue.push_back(to.clone());
+                }
+            }
+        }
+
+        for page in pages.pages() {
+            if !reachable.contains(page) {
+                problems.push(Problem::Unreachable(page.clone()));
+            }
+            if *page == Page::home() {
+                continue;
+            }
+            if self.backlinks(page).is_empty() {
+                problems.push(Problem::Orphan(page.clone()));
+            }
+            if !tree.contains(page) {
+                problems.push(Problem::NotInTree(page.clone()));
+            }
+            let entry = pages.get(page).expect("listed pages are registered");
+            if entry.related().unwrap_or_else(|| self.related(page)).is_empty() {
+                problems.push(Problem::NoFooterLinks(page.clone()));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui;
+
+    use super::*;
+    use crate::content::{Content, DEFAULT_CONTENT};
+    use crate::pages::PageEntry;
+    use crate::State;
+
+    #[derive(Default)]
+    struct TestPage {
+        links: Vec<&'static str>,
+        related: Option<Vec<Page>>,
+    }
+
+    impl PageEntry for TestPage {
+        fn title(&self) -> String {
+            String::new()
+        }
+
+        fn body(&self, _: &mut egui::Ui, _: &mut State) {}
+
+        fn links(&self) -> Vec<String> {
+            self.links.iter().map(|link| link.to_string()).collect()
+        }
+
+        fn related(&self) -> Option<Vec<Page>> {
+            self.related.clone()
+        }
+    }
+
+    fn validate(pages: Vec<(&str, TestPage)>, tree: &[&str]) -> Vec<Problem> {
+        let mut registry = Registry::default();
+        for (id, page) in pages {
+            registry.register(Page::new(id), page);
+        }
+        let tree: Vec<Page> = tree.iter().copied().map(Page::new).collect();
+        LinkGraph::new(&registry, &registry.link_targets()).validate(&registry, &tree)
+    }
+
+    #[test]
+    fn default_content_has_no_problems() {
+        let content = Content::from_toml(DEFAULT_CONTENT).expect("default content parses");
+        let tree: Vec<Page> = content.tree.iter().map(|entry| entry.page.clone()).collect();
+        let pages = crate::content_pages(content);
+        let graph = LinkGraph::new(&pages, &pages.link_targets());
+        let problems = graph.validate(&pages, &tree);
+        assert!(problems.is_empty(), "{problems:#?}");
+    }
+
+    #[test]
+    fn linked_pages_have_no_problems() {
+        let problems = validate(vec![
+            ("home", TestPage { links: vec!["a"], ..TestPage::default() }),
+            ("a", TestPage { links: vec!["home"], ..TestPage::default() }),
+        ], &["a"]);
+        assert_eq!(problems, []);
+    }
+
+    #[test]
+    fn reports_dead_links() {
+        let problems = validate(vec![
+            ("home", TestPage { links: vec!["a", "missing"], ..TestPage::default() }),
+            ("a", TestPage { related: Some(vec![Page::home(), Page::new("gone")]), ..TestPage::default() }),
+        ], &["a"]);
+        assert_eq!(problems, [
+            Problem::DeadLink { page: Page::new("a"), target: "gone".to_owned() },
+            Problem::DeadLink { page: Page::home(), target: "missing".to_owned() },
+        ]);
+    }
+
+    #[test]
+    fn reports_unreachable_orphans() {
+        let problems = validate(vec![
+            ("home", TestPage { links: vec!["a"], ..TestPage::default() }),
+            ("a", TestPage::default()),
+            ("b", TestPage { links: vec!["a"], ..TestPage::default() }),
+        ], &["a", "b"]);
+        assert_eq!(problems, [Problem::Unreachable(Page::new("b")), Problem::Orphan(Page::new("b"))]);
+    }
+
+    #[test]
+    fn reports_pages_missing_from_the_tree() {
+        let problems = validate(vec![
+            ("home", TestPage { links: vec!["a"], ..TestPage::default() }),
+            ("a", TestPage { links: vec!["home"], ..TestPage::default() }),
+        ], &[]);
+        assert_eq!(problems, [Problem::NotInTree(Page::new("a"))]);
+    }
+
+    #[test]
+    fn reports_pages_without_footer_links() {
+        let problems = validate(vec![
+            ("home", TestPage { links: vec!["a"], ..TestPage::default() }),
+            ("a", TestPage { related: Some(vec![]), ..TestPage::default() }),
+        ], &["a"]);
+        assert_eq!(problems, [Problem::NoFooterLinks(Page::new("a"))]);
+    }
+}
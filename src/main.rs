@@ -1,12 +1,57 @@
 //! Interactive Resume
 
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
 use eframe::egui;
-use egui_dock::TabStyle;
+use egui_dock::{NodeIndex, SurfaceIndex, TabIndex, TabStyle};
+
+use content::{
+    Content, PageContent, PageKind, Paragraph, Project, Section, Span, Status, TextStyle, TreeEntry,
+};
+use layout::{Breakpoint, Preset, SavedLayout, TabGroup, Undocked};
+use links::{LinkGraph, Problem};
+use markdown::{markdown_ui, split_wiki_links, Piece};
+use pages::{Page, PageEntry, Registry, Tab};
+use paragraph::RichParagraph;
+
+mod content;
+mod layout;
+mod links;
+mod markdown;
+mod pages;
+mod paragraph;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
 // ------------------------------------------------------------------------------------------------
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
+    env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+
+    // Usage: website [check] [RESUME]
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let check = args.first().is_some_and(|arg| arg == "check");
+    if check {
+        args.remove(0);
+    }
+    let content = match args.first() {
+        Some(path) => std::fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("failed to read {path}: {err}");
+            std::process::exit(1);
+        }),
+        None => content::DEFAULT_CONTENT.to_owned(),
+    };
+    let content = Content::from_toml(&content).unwrap_or_else(|err| {
+        eprintln!("failed to parse resume content: {err}");
+        std::process::exit(1);
+    });
+
+    if check {
+        std::process::exit(check_links(content));
+    }
+
     let _ = eframe::run_native(
         "website",
         eframe::NativeOptions {
@@ -16,21 +61,38 @@ fn main() {
             },
             ..Default::default()
         },
-        Box::new(|cc| Box::new(Resume::new(cc))),
+        Box::new(|cc| Box::new(Resume::new(cc, content))),
     );
 }
 
+/// Prints every navigation problem of the site, returning the exit code.
+#[cfg(not(target_arch = "wasm32"))]
+fn check_links(content: Content) -> i32 {
+    let tree: Vec<Page> = content.tree.iter().map(|entry| entry.page.clone()).collect();
+    let pages = content_pages(content);
+    let graph = LinkGraph::new(&pages, &pages.link_targets());
+    let problems = graph.validate(&pages, &tree);
+    for problem in &problems {
+        println!("{problem}");
+    }
+    println!("{} pages, {} problems", pages.pages().count(), problems.len());
+    i32::from(!problems.is_empty())
+}
+
 #[cfg(target_arch = "wasm32")]
 fn main() {
     // Redirect `log` message to `console.log` and friends:
     eframe::WebLogger::init(log::LevelFilter::Debug).ok();
 
+    let content = Content::from_toml(content::DEFAULT_CONTENT)
+        .expect("failed to parse resume content");
+
     wasm_bindgen_futures::spawn_local(async {
         eframe::WebRunner::new()
             .start(
                 "the_canvas_id", // hardcode it
                 eframe::WebOptions::default(),
-                Box::new(|cc| Box::new(Resume::new(cc))),
+                Box::new(|cc| Box::new(Resume::new(cc, content))),
             )
             .await
             .expect("failed to start eframe");
@@ -40,57 +102,148 @@ fn main() {
 // ------------------------------------------------------------------------------------------------
 
 pub struct Resume {
-    pages: egui_dock::DockState<Page>,
+    pages: egui_dock::DockState<Tab>,
     viewer: PageViewer,
+    #[cfg(target_arch = "wasm32")]
+    history: web::BrowserHistory,
+    observers: Vec<Box<dyn RequestObserver>>,
+    /// The window size class of the last frame.
+    breakpoint: Breakpoint,
+    /// Where the pointer went down, for recognizing swipes in narrow windows.
+    swipe_start: Option<egui::Pos2>,
+    /// The site tree, for when every page is closed.
+    tree: Vec<TreeEntry>,
+    search: String,
+    /// Pages in their own OS windows, which are only supported by the native build.
+    undocked: Vec<Undocked>,
+    groups: Vec<TabGroup>,
+    /// The name typed for saving the open tabs as a group.
+    group_name: String,
+    /// The undocked tab whose window should come to the front.
+    #[cfg(not(target_arch = "wasm32"))]
+    focus_undocked: Option<Tab>,
 }
 
 impl eframe::App for Resume {
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        self.viewer.now = ctx.input(|input| input.time);
+        // Fits the default layout to the window, starting with the first frame.
+        let breakpoint = Breakpoint::of_width(ctx.screen_rect().width());
+        if breakpoint != self.breakpoint {
+            if let Some(dock) = layout::rebalance(&self.pages, &self.viewer.pages, self.breakpoint, breakpoint) {
+                self.set_dock(dock);
+            }
+            self.breakpoint = breakpoint;
+        }
+        // Narrow windows show one page at a time instead of the dock.
+        let narrow = self.breakpoint == Breakpoint::Phone;
         egui::TopBottomPanel::top("top-bar").exact_height(41.0).show(ctx, |ui| {
             ui.visuals_mut().button_frame = false;
             ui.style_mut().spacing.item_spacing = egui::vec2(19.0, 3.0);
             ui.horizontal_centered(|ui| {
-                if ui.button("Home").on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
-                    self.viewer.state.requests.push(Request::OpenPage(Page::Home));
+                let history = &self.viewer.state.history;
+                let back = ui.add_enabled(history.can_go_back(), egui::Button::new("⏴"))
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .on_hover_text("Back (Alt+Left)");
+                let forward = ui.add_enabled(history.can_go_forward(), egui::Button::new("⏵"))
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .on_hover_text("Forward (Alt+Right)");
+                if back.clicked() {
+                    self.viewer.state.requests.push(Request::Back);
                 }
-                if ui.button("Help").on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
-                    self.viewer.state.requests.push(Request::OpenPage(Page::Help));
+                if forward.clicked() {
+                    self.viewer.state.requests.push(Request::Forward);
                 }
-                if ui.button("Contact").on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
-                    self.viewer.state.requests.push(Request::OpenPage(Page::Contact));
+                if narrow {
+                    ui.menu_button("☰", |ui| {
+                        if self.page_buttons(ui) | self.share_button(ui) {
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        self.recently_closed_menu(ui);
+                        ui.menu_button("Groups", |ui| self.groups_menu(ui));
+                        self.layout_menu(ui);
+                    });
+                } else {
+                    self.page_buttons(ui);
                 }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     let visuals = ui.visuals().clone();
                     if let Some(new_visuals) = visuals.light_dark_small_toggle_button(ui) {
                         ui.ctx().set_visuals(new_visuals);
                     }
+                    // Narrow windows have these in the ☰ menu.
+                    if narrow {
+                        return;
+                    }
+                    self.recently_closed_menu(ui);
+                    ui.menu_button("Groups", |ui| self.groups_menu(ui));
+                    self.layout_menu(ui);
+                    self.share_button(ui);
                 });
             });
         });
-        egui_dock::DockArea::new(&mut self.pages)
-            .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
-            .show(ctx, &mut self.viewer);
-
-        for req in self.viewer.state.requests.drain(..).collect::<Vec<_>>() {
-            match req {
-                Request::OpenPage(page) => {
-                    if let Some(ids) = self.pages.find_tab(&page) {
-                        self.pages.set_active_tab(ids);
-                        self.viewer.highlight_page = Some(page);
-                    } else {
-                        self.pages.push_to_focused_leaf(page);
-                    }
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::ALT, egui::Key::ArrowLeft)) {
+            self.viewer.state.requests.push(Request::Back);
+        }
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::ALT, egui::Key::ArrowRight)) {
+            self.viewer.state.requests.push(Request::Forward);
+        }
+        let reopen = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+        if ctx.input_mut(|input| input.consume_key(reopen, egui::Key::T)) {
+            self.viewer.state.requests.push(Request::ReopenClosed);
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some(page) = self.history.take_popped() {
+            self.viewer.state.requests.push(Request::BrowserNavigated(page));
+        }
+        self.viewer.open_tabs = self.pages.iter_all_tabs().count();
+        if narrow {
+            self.narrow_ui(ctx);
+        } else if self.viewer.open_tabs == 0 {
+            egui::CentralPanel::default().show(ctx, |ui| self.empty_ui(ui));
+        } else {
+            // Closing a floating window drops its tabs without `TabViewer::on_close`.
+            let floating: Vec<Tab> = self.pages.iter_all_tabs()
+                .filter(|((surface, _), _)| !surface.is_main())
+                .map(|(_, tab)| tab.clone())
+                .collect();
+            egui_dock::DockArea::new(&mut self.pages)
+                .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
+                .show(ctx, &mut self.viewer);
+            for tab in floating {
+                if self.pages.find_tab(&tab).is_none() {
+                    self.viewer.state.remember_closed(tab.page);
                 }
             }
         }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.undocked_ui(ctx);
+
+        self.drain_requests();
+        if !self.viewer.state.requests.is_empty() {
+            ctx.request_repaint();
+        }
+        if let Some((_, start)) = &self.viewer.highlight_tab {
+            if self.viewer.now - start < HIGHLIGHT_SECONDS {
+                ctx.request_repaint();
+            } else {
+                self.viewer.highlight_tab = None;
+            }
+        }
         // ctx.request_repaint_after(std::time::Duration::from_millis(50));
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let pinned = self.viewer.state.pinned.iter().cloned().collect();
+        SavedLayout::new(self.pages.clone(), self.undocked.clone(), pinned, self.groups.clone()).save(storage);
+    }
 }
 
 impl Resume {
-    pub fn new(cc: &eframe::CreationContext) -> Self {
+    pub fn new(cc: &eframe::CreationContext, content: Content) -> Self {
         use egui::{FontFamily::*, FontId, TextStyle};
-        egui_extras::install_image_loaders(&cc.egui_ctx);
         // let mut fonts = egui::FontDefinitions::default();
         // fonts.font_data.insert(
         //     "JetBrainsMono Regular".to_owned(),
@@ -111,612 +264,1273 @@ impl Resume {
             ].into(),
             ..Default::default()
         });
-        
-        let mut pages = egui_dock::DockState::new(vec![Page::Home]);
+        Self::load(&cc.egui_ctx, cc.storage, content)
+    }
+
+    /// Sets up the pages of `content` in the layout of the last session, or the default layout.
+    fn load(ctx: &egui::Context, storage: Option<&dyn eframe::Storage>, content: Content) -> Self {
+        egui_extras::install_image_loaders(ctx);
+        let keep_last_tab = content.keep_last_tab;
+        let entries = content.tree.clone();
+        let tree: Vec<Page> = entries.iter().map(|entry| entry.page.clone()).collect();
+        let registry = content_pages(content);
+        let targets = registry.link_targets();
+        // A `#/<page id>` link opens that page on top of the layout.
+        #[cfg(target_arch = "wasm32")]
+        let requests = match web::page_from_hash(&web::location_hash()) {
+            Some(page) if registry.contains(&page) => vec![Request::OpenPage(page, Placement::Focused)],
+            Some(page) => {
+                log::warn!("unknown page `{}` in the URL", page.id());
+                vec![]
+            }
+            None => vec![],
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let requests = vec![];
+        let mut state = State {
+            requests,
+            history: History::default(),
+            closed: vec![],
+            pinned: BTreeSet::new(),
+            scroll_to: None,
+            graph: LinkGraph::new(&registry, &targets),
+            targets,
+        };
+        for problem in state.graph.validate(&registry, &tree) {
+            match problem {
+                Problem::DeadLink { .. } => log::error!("{problem}"),
+                _ => log::warn!("{problem}"),
+            }
+        }
 
-        // You can modify the tree before constructing the dock
-        let [_a, b] = pages.main_surface_mut()
-            .split_left(egui_dock::NodeIndex::root(), 0.3, vec![Page::Contact]);
-        let [_a, _b] = pages.main_surface_mut()
-            .split_below(b, 0.5, vec![Page::Help]);
+        let saved = layout::load_layout(storage, &registry).unwrap_or_else(|| {
+            SavedLayout::new(layout::default_layout(Breakpoint::Desktop, &registry), vec![], vec![], vec![])
+        });
+        // A shared layout link wins over the layout of the last session.
+        #[cfg(target_arch = "wasm32")]
+        let pages = web::location_hash()
+            .strip_prefix("#layout=")
+            .and_then(|text| layout::decode_layout(text, &registry))
+            .unwrap_or(saved.dock);
+        #[cfg(not(target_arch = "wasm32"))]
+        let pages = saved.dock;
+        state.pinned = saved.pinned.into_iter().collect();
 
-        Self {
-            pages,
+        let mut resume = Self {
+            pages: egui_dock::DockState::new(vec![]),
             viewer: PageViewer {
-                state: State {
-                    requests: vec![],
-                },
-                highlight_page: Some(Page::Help),
+                state,
+                pages: registry,
+                highlight_tab: None,
+                now: 0.0,
+                open_tabs: 0,
+                keep_last_tab,
             },
+            #[cfg(target_arch = "wasm32")]
+            history: web::BrowserHistory::new(ctx),
+            observers: vec![],
+            breakpoint: Breakpoint::Desktop,
+            swipe_start: None,
+            tree: entries,
+            undocked: saved.undocked,
+            groups: saved.groups,
+            group_name: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            focus_undocked: None,
+            search: String::new(),
+        };
+        resume.add_observer(LogRequests);
+        resume.set_dock(pages);
+        // Going back to the entry the visitor landed on should open a page too.
+        #[cfg(target_arch = "wasm32")]
+        if web::page_from_hash(&web::location_hash()).is_none() {
+            let first = resume.pages.iter_all_tabs().next().map(|(_, tab)| tab.page.clone());
+            if let Some(page) = resume.focused_page().or(first) {
+                resume.history.replace(&page);
+            }
         }
+        resume
     }
-}
-
-pub struct State {
-    requests: Vec<Request>,
-}
-
-pub struct PageViewer {
-    state: State,
-    highlight_page: Option<Page>,
-}
-
-impl egui_dock::TabViewer for PageViewer {
-    type Tab = Page;
-    
-    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
-        match tab {
-            Page::Home => "Home".into(),
-            Page::Help => "Help".into(),
-            Page::Contact => "Contact".into(),
-
-            Page::Skills => "Skills".into(),
-            Page::Experience => "Experience".into(),
-            Page::Portfolio => "Portfolio".into(),
-            Page::Education => "Education".into(),
-            Page::WorkHistory => "Work History".into(),
-            Page::Goals => "Goals".into(),
 
-            Page::Project1 => "Markdown Editor".into(),
-            Page::Project2 => "Modular Programming Model".into(),
-            Page::Project3 => "EEV Data Model".into(),
+    /// Passes the pending requests through the observers and handles the ones they let through.
+    fn drain_requests(&mut self) {
+        let requests = std::mem::take(&mut self.viewer.state.requests);
+        #[cfg(target_arch = "wasm32")]
+        let (mut handled, mut navigated) = (false, false);
+        for req in requests {
+            let Some(req) = self.observers.iter_mut().try_fold(req, |req, observer| observer.before(req)) else {
+                continue;
+            };
+            #[cfg(target_arch = "wasm32")]
+            {
+                handled = true;
+                navigated |= req.navigates();
+            }
+            self.handle(req.clone());
+            for observer in &mut self.observers {
+                observer.after(&req, &mut self.viewer.state.requests);
+            }
+        }
+        // Mirror the page the requests focused in the URL, in a new browser history entry if they
+        // navigated.
+        #[cfg(target_arch = "wasm32")]
+        if let Some(page) = self.focused_page().filter(|_| handled) {
+            if navigated {
+                self.history.push(&page);
+            } else {
+                self.history.replace(&page);
+            }
         }
     }
-    
-    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
-        match tab {
-            Page::Home => home_page(ui, &mut self.state),
-            Page::Help => help_page(ui, &mut self.state),
-            Page::Contact => contact_page(ui, &mut self.state),
 
-            Page::Skills => skills_page(ui, &mut self.state),
-            Page::Experience => experience_page(ui, &mut self.state),
-            Page::Education => education_page(ui, &mut self.state),
-            Page::Portfolio => portfolio_page(ui, &mut self.state),
-            Page::WorkHistory => work_history_page(ui, &mut self.state),
-            Page::Goals => goals_page(ui, &mut self.state),
-
-            Page::Project1 => project_1_page(ui, &mut self.state),
-            Page::Project2 => project_2_page(ui, &mut self.state),
-            Page::Project3 => project_3_page(ui, &mut self.state),
+    /// Links to the main pages, returning whether one was clicked.
+    fn page_buttons(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut clicked = false;
+        let pages = [("Home", Page::home()), ("Help", Page::new("help")), ("Contact", Page::new("contact"))];
+        for (title, page) in pages {
+            let button = ui.button(title).on_hover_cursor(egui::CursorIcon::PointingHand);
+            if let Some(placement) = Placement::of_click(&button) {
+                self.viewer.state.requests.push(Request::OpenPage(page, placement));
+                clicked = true;
+            }
         }
+        clicked
     }
 
-    fn tab_style_override(&self, _tab: &Self::Tab, global_style: &TabStyle) -> Option<TabStyle> {
-        let mut style = global_style.clone();
-        style.tab_body.inner_margin = 13.0.into();
-        Some(style)
+    /// Restores and deletes tab groups, and saves the open tabs as a new one.
+    /// Reopens closed pages, disabled while there are none.
+    fn recently_closed_menu(&mut self, ui: &mut egui::Ui) {
+        let closed = self.recently_closed();
+        ui.add_enabled_ui(!closed.is_empty(), |ui| {
+            ui.menu_button("Recently closed", |ui| {
+                for page in closed {
+                    let button = ui.button(self.viewer.pages.title(&page));
+                    if let Some(placement) = Placement::of_click(&button) {
+                        self.viewer.state.requests.push(Request::OpenPage(page, placement));
+                        ui.close_menu();
+                    }
+                }
+            }).response.on_hover_text("Reopen the last one with Ctrl+Shift+T");
+        });
     }
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum Page {
-    Home,
-    Help,
-    Contact,
-
-    Skills,
-    Experience,
-    Portfolio,
-    WorkHistory,
-    Education,
-
-    Goals,
-
-    Project1,
-    Project2,
-    Project3,
-}
-
-pub enum Request {
-    OpenPage(Page),
-}
-
-// ------------------------------------------------------------------------------------------------
 
-fn home_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "home",
-            |ui, state| {
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false, false])
-                    .show(ui, |ui| {
-                        ui.style_mut().spacing.item_spacing = egui::vec2(3.0, 7.0);
-                        ui.heading("Welcome!");
-                        ui.weak("This is the personal website of Matthew Norman.");
-                        ui.strong("The blue text pieces are internal links to pages, and you can click and drag tabs to rearrange them. You can click the button in the upper left to change the theme.");
-                        ui.horizontal_wrapped(|ui| {
-                            ui.style_mut().spacing.item_spacing = egui::vec2(1.0, 0.0);
-                            ui.label("If this is your first time visiting, I'd suggest you visit the ");
-                            if ui.link("Help page").clicked() {
-                                state.requests.push(Request::OpenPage(Page::Help));
-                            }
-                            ui.label(" for a quick guide on how this website works and a short overview of the design.");
-                        });
-                        ui.separator();
-                        ui.heading("Explore");
-                        ui.add_space(19.0);
-                        tree_item(ui, state, Page::Help, "Help", 0);
-                        // tree_item(ui, state, Page::HelpNavigation, "Navigation Guide", 1);
-                        tree_item(ui, state, Page::Contact, "Contact", 0);
-                        tree_item(ui, state, Page::Skills, "Skills", 0);
-                        tree_item(ui, state, Page::Experience, "Experience", 0);
-                        tree_item(ui, state, Page::Portfolio, "Portfolio", 1);
-                        tree_item(ui, state, Page::WorkHistory, "Work History", 1);
-                        // tree_item(ui, state, Page::WorkHistory, "IT Intern", 2);
-                        tree_item(ui, state, Page::Education, "Education", 0);
-                        tree_item(ui, state, Page::Goals, "Goals", 0);
-                        ui.add_space(19.0);
-                        ui.weak("Last Updated: 6/20/2024")
-                    });
-            },
-            |ui, _state| {
-                ui.heading("Home");
-            },
-            |ui, state| {
-                if ui.link("Help").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Help));
+    /// Replaces the open tabs with a preset or the default layout.
+    fn layout_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Layout", |ui| {
+            for preset in Preset::ALL {
+                if ui.button(preset.name()).clicked() {
+                    self.viewer.state.requests.push(Request::UsePreset(preset));
+                    ui.close_menu();
                 }
-                if ui.link("Contact").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Contact));
-                }
-            },
-    );
-}
+            }
+            ui.separator();
+            if ui.button("Reset Layout").clicked() {
+                self.viewer.state.requests.push(Request::ResetLayout);
+                ui.close_menu();
+            }
+        });
+    }
 
-fn help_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "help",
-            |ui, _state| {
-                ui.style_mut().spacing.item_spacing = egui::vec2(3.0, 7.0);
-                ui.weak("✱ Try clicking and dragging pages around!");
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    ui.heading("Navigation");
-                    ui.separator();
-                    ui.label("You can click and drag tab title bars to reorient them. Try to drag this page onto the Home page and take note of the popup that gives you the option to layout the pages in different ways.");
-                    ui.label("There are links highlighted all across pages on this site. They can take you to new pages which will provide more information. Kind of like Wikipedia.");
-                });
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    ui.heading("Site Overview");
-                    ui.separator();
-                    ui.label("This is a project to develop an interactive resume website for school (Florida State University) where I can show off my talents to potential employers.");
-                    ui.label("This website is designed similarly to how I developed another project, a desktop application designed for organizing data/files. The idea is to present it similarly to how a desktop application designed for viewing/creating resumes would be presented.");
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
-                }
-                ui.heading("Help");
-            },
-            |ui, state| {
-                if ui.link("Home").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+    fn groups_menu(&mut self, ui: &mut egui::Ui) {
+        for group in &self.groups {
+            ui.horizontal(|ui| {
+                if ui.button(&group.name).clicked() {
+                    self.viewer.state.requests.push(Request::RestoreGroup(group.name.clone()));
+                    ui.close_menu();
                 }
-                if ui.link("Contact").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Contact));
+                if ui.small_button("🗑").on_hover_text("Delete the group").clicked() {
+                    self.viewer.state.requests.push(Request::DeleteGroup(group.name.clone()));
                 }
-            },
-    );
-}
+            });
+        }
+        if !self.groups.is_empty() {
+            ui.separator();
+        }
+        ui.horizontal(|ui| {
+            let name = egui::TextEdit::singleline(&mut self.group_name).hint_text("Group name");
+            ui.add(name.desired_width(140.0));
+            let name = self.group_name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("Save open tabs")).clicked() {
+                self.viewer.state.requests.push(Request::SaveGroup(name.to_owned()));
+                self.group_name.clear();
+                ui.close_menu();
+            }
+        });
+    }
 
-fn contact_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "contact",
-            |ui, _state| {
-                ui.style_mut().spacing.item_spacing = egui::vec2(7.0, 7.0);
-                ui.heading("Matthew Norman");
-                ui.separator();
-                // ui.label("📞 (850) 555-5555");
-                ui.horizontal_wrapped(|ui| {
-                    ui.strong("Email:");
-                    ui.label("mmn23a@fsu.edu");
+    fn share_button(&self, ui: &mut egui::Ui) -> bool {
+        let floating = !self.undocked.is_empty() || self.pages.iter_surfaces()
+            .any(|surface| matches!(surface, egui_dock::Surface::Window(..)));
+        let share = ui.button("Share")
+            .on_hover_cursor(egui::CursorIcon::PointingHand)
+            .on_hover_text(match floating {
+                true => "Copy a link that opens this layout, without the tabs in their own windows",
+                false => "Copy a link that opens this layout",
+            });
+        if share.clicked() {
+            ui.ctx().copy_text(layout::share_url(&self.pages));
+        }
+        share.clicked()
+    }
+
+    /// Shows the focused page alone, with a switcher for the open pages in place of the tabs.
+    /// Swiping sideways moves between the open pages.
+    fn narrow_ui(&mut self, ctx: &egui::Context) {
+        let open: Vec<Tab> = self.pages.iter_all_tabs().map(|(_, tab)| tab.clone()).collect();
+        let current = self.focused_tab().or_else(|| open.first().cloned());
+        egui::TopBottomPanel::top("page-switcher").show(ctx, |ui| {
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for tab in &open {
+                        let selected = current.as_ref() == Some(tab);
+                        if ui.selectable_label(selected, self.viewer.tab_title(tab)).clicked() && !selected {
+                            self.viewer.state.requests.push(Request::FocusTab(tab.clone()));
+                        }
+                    }
                 });
-                ui.hyperlink_to(" GitHub", "https://www.github.com/mmnorm");
-                ui.hyperlink_to(" LinkedIn", "https://www.linkedin.com/in/matthew-norman-67b10025a/");
-                ui.separator();
+            });
+        });
+        let body = egui::CentralPanel::default().show(ctx, |ui| match current.clone() {
+            // Keyed like `TabViewer::id`, so every tab keeps its own scroll position.
+            Some(mut tab) => {
+                ui.push_id(egui::Id::new(&tab), |ui| egui_dock::TabViewer::ui(&mut self.viewer, ui, &mut tab));
+            }
+            None => self.empty_ui(ui),
+        });
 
-                ui.add(egui::Image::new(
-                    "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/headshot2.jpg"
-                ).rounding(11.0))
+        let Some(step) = self.swipe(ctx, body.response.rect) else {
+            return;
+        };
+        let index = current.and_then(|current| open.iter().position(|tab| *tab == current));
+        let next = index.and_then(|index| index.checked_add_signed(step)).and_then(|index| open.get(index));
+        if let Some(next) = next {
+            self.viewer.state.requests.push(Request::FocusTab(next.clone()));
+        }
+    }
 
-                    .on_hover_text("This is a photo of me. Trust me, I look way better in person.");
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
-                }
-                ui.heading("Contact Information");
-            },
-            |ui, state| {
-                if ui.link("Help").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Help));
+    /// Shown instead of the dock once every page is closed.
+    fn empty_ui(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            ui.heading("No pages are open");
+            ui.weak("Open a page from the site tree or search for one, or go back to the default layout.");
+            ui.add_space(13.0);
+            if ui.button("Restore default layout").clicked() {
+                self.viewer.state.requests.push(Request::ResetLayout);
+            }
+            ui.add_space(13.0);
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("🔍 Search pages"));
+            ui.separator();
+            let state = &mut self.viewer.state;
+            let pages = &self.viewer.pages;
+            let search = self.search.trim().to_lowercase();
+            if search.is_empty() {
+                for entry in &self.tree {
+                    tree_item(ui, state, entry.page.clone(), &pages.title(&entry.page), entry.level);
                 }
-            },
-    );
-}
+                return;
+            }
+            let found: Vec<Page> = pages.pages()
+                .filter(|page| pages.title(page).to_lowercase().contains(&search))
+                .cloned()
+                .collect();
+            if found.is_empty() {
+                ui.weak("No page matches the search.");
+            }
+            for page in found {
+                let title = pages.title(&page);
+                tree_item(ui, state, page, &title, 0);
+            }
+        });
+    }
 
-fn skills_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "skills",
-            |ui, _state| {
-                ui.group(|ui| {
-                    ui.heading("Programming");
-                    ui.separator();
-                    ui.label("I am proficient in Rust, Python, and C. However, my primary language for the past couple years has been Rust. In fact, this whole website is written in Rust and complied to Web Assembly. I would consider my programming skills to be well above average for my age/peers.");
-                });
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    ui.heading("System Administration");
-                    ui.separator();
-                    ui.label("I've been using Linux full-time for about a year now. I have extensive experience in low-level system administration and am extremely familiar with a terminal environment. I'd say I'm more comfortable working with Linux than I am with Windows now.");
-                });
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    ui.heading("System Design");
-                    ui.separator();
-                    ui.label("I've developed and maintained several large-scale and small-scale projects that rely on effective information architecture and design. Organization is one of my strongest attributes. I am an excellent critical thinker and capable of planning complex systems without oversight.");
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+    /// Shows every undocked tab in its own OS window.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn undocked_ui(&mut self, ctx: &egui::Context) {
+        for undocked in &mut self.undocked {
+            let tab = undocked.tab.clone();
+            let mut builder = egui::ViewportBuilder::default().with_title(self.viewer.tab_title(&tab));
+            // Placing the window again in later frames would fight the OS moving it.
+            if !undocked.created {
+                builder = builder.with_inner_size(undocked.size.unwrap_or(egui::vec2(640.0, 720.0)));
+                if let Some(position) = undocked.position {
+                    builder = builder.with_position(position);
                 }
-                ui.heading("Skills");
-            },
-            |ui, state| {
-                if ui.link("Experience").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Experience));
+                undocked.created = true;
+            }
+            let id = egui::ViewportId::from_hash_of(("undocked", &tab));
+            ctx.show_viewport_immediate(id, builder, |ctx, _| {
+                if self.focus_undocked.as_ref() == Some(&tab) {
+                    self.focus_undocked = None;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
                 }
-            },
-    );
-}
-
-fn experience_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "experience",
-            |ui, state| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.style_mut().spacing.item_spacing = egui::vec2(0.0, 0.0);
-                    ui.label("I would divide my current level of experience into two categories: ");
-                    if ui.link("my personal/side projects").clicked() {
-                        state.requests.push(Request::OpenPage(Page::Portfolio));
+                egui::TopBottomPanel::top(format!("{}-undocked", tab.name())).show(ctx, |ui| {
+                    let dock = ui.button("Dock").on_hover_text("Move the page back into the main window");
+                    if dock.clicked() {
+                        self.viewer.state.requests.push(Request::Redock(tab.clone()));
                     }
-                    ui.label(", and ");
-                    if ui.link("my employment experience").clicked() {
-                        state.requests.push(Request::OpenPage(Page::WorkHistory));
-                    }
-                    ui.label(".");
                 });
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    ui.style_mut().spacing.item_spacing = egui::vec2(0.0, 0.0);
-                    let heading = egui::RichText::new("Personal Projects").heading();
-                    if ui.link(heading).clicked() {
-                        state.requests.push(Request::OpenPage(Page::Portfolio));
-                    }
-                    ui.separator();
-                    ui.horizontal_wrapped(|ui| {
-                        ui.label("As mentioned in ");
-                        if ui.link("my Goals Page").clicked() {
-                            state.requests.push(Request::OpenPage(Page::Goals));
-                        }
-                        ui.label(", I am quite independently motivated. My personal projects hep feed my passion for computing in all forms and I'm quite proud of my choice to use my free time toward them these past few years.");
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    // Every OS window shows its page in the same central panel.
+                    ui.push_id(egui::Id::new(&tab), |ui| {
+                        egui_dock::TabViewer::ui(&mut self.viewer, ui, &mut tab.clone());
                     });
                 });
-                ui.add_space(19.0);
-                ui.group(|ui| {
-                    let heading = egui::RichText::new("Employment History").heading();
-                    if ui.link(heading).clicked() {
-                        state.requests.push(Request::OpenPage(Page::WorkHistory));
-                    }
-                    ui.separator();
-                    ui.label("Nothing special here, just real-world experience that I have cited in this resume.");
+                let (close, outer, inner) = ctx.input(|input| {
+                    let viewport = input.viewport();
+                    (viewport.close_requested(), viewport.outer_rect, viewport.inner_rect)
                 });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
-                }
-                ui.heading("Experience");
-            },
-            |ui, state| {
-                if ui.link("Skills").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Skills));
-                }
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+                undocked.position = outer.map(|outer| outer.min).or(undocked.position);
+                undocked.size = inner.map(|inner| inner.size()).or(undocked.size);
+                if close {
+                    self.viewer.state.requests.push(Request::CloseTab(tab.clone()));
                 }
-                if ui.link("Work History").clicked() {
-                    state.requests.push(Request::OpenPage(Page::WorkHistory));
-                }
-            },
-    );
-}
+            });
+        }
+    }
 
-fn portfolio_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "portfolio",
-            |ui, state| {
-                page_object(ui, state, Some(Page::Project1), "Markdown Editor", |ui, _state| {
-                    ui.label("A markdown editor in the same vein as modern note-taking applications.");
-                    ui.weak("Click `Learn More` for details");
-                });
-                ui.add_space(19.0);
-                page_object(ui, state, Some(Page::Project2), "Modular Programming Model", |ui, _state| {
-                    ui.label("A programming model for building extremely scalable applications without oversight.");
-                    ui.weak("Click `Learn More` for details");
-                });
-                // ui.add_space(19.0);
-                // page_object(ui, state, Some(Page::Project3), "EEV Data Model", |ui, _state| {
-                //     ui.weak("Click `Learn More` for details");
-                // });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+    /// Recognizes sideways swipes starting in `area`: 1 for swiping to the left, -1 for swiping to
+    /// the right.
+    fn swipe(&mut self, ctx: &egui::Context, area: egui::Rect) -> Option<isize> {
+        let (pressed, released, pos) = ctx.input(|input| {
+            (input.pointer.any_pressed(), input.pointer.any_released(), input.pointer.latest_pos())
+        });
+        if pressed {
+            self.swipe_start = pos.filter(|pos| area.contains(*pos));
+        }
+        if !released {
+            return None;
+        }
+        let delta = pos? - self.swipe_start.take()?;
+        let sideways = delta.x.abs() > 80.0 && delta.x.abs() > 2.0 * delta.y.abs();
+        sideways.then_some(if delta.x < 0.0 { 1 } else { -1 })
+    }
+
+    /// Carries out a request that passed the observers.
+    fn handle(&mut self, req: Request) {
+        match req {
+            Request::OpenPage(page, placement) => {
+                self.visit(&page);
+                if let Some(tab) = self.open_page(page, placement) {
+                    self.highlight(tab);
                 }
-                ui.heading("Portfolio");
-            },
-            |ui, state| {
-                if ui.link("Experience").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Experience));
+            }
+            Request::OpenInSplit { page, direction } => {
+                self.visit(&page);
+                match self.focus_page(&page) {
+                    Some(tab) => self.highlight(tab),
+                    None => {
+                        let tab = self.new_tab(page);
+                        self.split_page(tab, direction);
+                    }
                 }
-                if ui.link("Work History").clicked() {
-                    state.requests.push(Request::OpenPage(Page::WorkHistory));
+            }
+            Request::FocusPage(page) => {
+                if self.is_open(&page) {
+                    self.visit(&page);
+                    self.focus_page(&page);
                 }
-            },
-    );
-}
-
-fn project_1_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "project1",
-            |ui, _state| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Role:");
-                    ui.label("Developer; the sole programmer, designer, and maintainer of the project.");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Status:");
-                    ui.colored_label(egui::Color32::LIGHT_GREEN, "IN PROGRESS");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Overview:");
-                    ui.label("A simple markdown editor primarily designed for usage in Linux. It was built to run very quickly, even on older computers. The design is very similar to some popular note-taking applications with links, preview support, and customization capability.");
-                });
-                ui.add_space(19.0);
-                ui.heading("Showcase:");
-                ui.vertical_centered(|ui| {
-                    ui.add(egui::Image::new(
-                        "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/p1_a.png"
-                    )
-                        .rounding(11.0))
-                        .on_hover_text("Markdown editor preview mode showcase");
-                    ui.weak("Editor preview mode example.");
-                    ui.hyperlink_to("Click here to view a full-size version of the image", "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/p1_a.png");
-                    // ui.add(egui::Image::new(
-                    //     "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/p1_b.png"
-                    // )
-                    //     .rounding(11.0))
-                    //     .on_hover_text("Markdown editor editing mode showcase");
-                    // ui.weak("Editor editing mode example.");
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+            }
+            Request::FocusTab(tab) => {
+                if self.pages.find_tab(&tab).is_some() {
+                    self.visit(&tab.page);
+                    self.focus_tab(&tab);
                 }
-                ui.heading("Markdown Editor");
-            },
-            |ui, state| {
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+            }
+            Request::ClosePage(page) => self.close_page(&page),
+            Request::CloseTab(tab) => self.close_tab(&tab),
+            Request::CloseOthers(tab) => {
+                for other in self.leaf_tabs(&tab).into_iter().filter(|other| *other != tab) {
+                    self.close_tab(&other);
                 }
-                if ui.link("Skills").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Skills));
+            }
+            Request::CloseToTheRight(tab) => {
+                let tabs = self.leaf_tabs(&tab);
+                let right = tabs.iter().skip_while(|other| **other != tab).skip(1);
+                for other in right {
+                    self.close_tab(other);
                 }
-            },
-    );
-}
-
-fn project_2_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "project2",
-            |ui, _state| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Role:");
-                    ui.label("Designer; the model's creator. This project was part of a larger programming project's design phase and I served as the lead.");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Status:");
-                    ui.colored_label(egui::Color32::YELLOW, "UNDER REVIEW");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Overview:");
-                    ui.label("A set of guidelines for program development. The goal was to design a general heuristic for developing programs that are small in size, but extremely modular. These programs would then, in turn, be scalable to indefinite proportions.");
-                });
-                ui.add_space(19.0);
-                ui.heading("Showcase:");
-                ui.vertical_centered(|ui| {
-                    ui.add(egui::Image::new(
-                        "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/p2_a.png"
-                    )
-                        .rounding(11.0))
-                        .on_hover_text("Chart for the program development model");
-                    ui.weak("Program development model chart.");
-                    ui.hyperlink_to("Click here to view a full-size version of the image", "https://raw.githubusercontent.com/MMNorm/personal-website/master/assets/p2_a.png");
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+            }
+            Request::MoveToSplit { tab, direction } => {
+                let Some((surface, node, index)) = self.pages.find_tab(&tab) else {
+                    return;
+                };
+                // The last tab of a leaf has nothing to split from.
+                if self.pages[surface][node].tabs_count() < 2 {
+                    return;
                 }
-                ui.heading("Modular Programming Model");
-            },
-            |ui, state| {
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+                self.pages.remove_tab((surface, node, index));
+                let [_, new] = self.pages.split((surface, node), direction, 0.5, egui_dock::Node::leaf(tab));
+                self.pages.set_focused_node_and_surface((surface, new));
+            }
+            Request::Duplicate(tab) => {
+                let Some((surface, node, index)) = self.pages.find_tab(&tab) else {
+                    return;
+                };
+                let duplicate = self.new_tab(tab.page);
+                self.pages[surface][node].insert_tab(TabIndex(index.0 + 1), duplicate.clone());
+                self.focus_tab(&duplicate);
+            }
+            Request::ScrollTo { page, anchor, placement } => {
+                self.visit(&page);
+                if let Some(tab) = self.open_page(page.clone(), placement) {
+                    self.highlight(tab);
                 }
-                if ui.link("Skills").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Skills));
+                self.viewer.state.scroll_to = Some((page, pages::link_key(&anchor)));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Request::Undock(tab) => {
+                if let Some(ids) = self.pages.find_tab(&tab) {
+                    self.pages.remove_tab(ids);
+                    self.undocked.push(Undocked::new(tab));
                 }
-            },
-    );
-}
-
-fn project_3_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "project3",
-            |ui, _state| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Role:");
-                    ui.label("Creator");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Status:");
-                    ui.colored_label(egui::Color32::YELLOW, "UNDER REVIEW");
-                });
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("Overview:");
-                    ui.label("A data model designed to mitigate the limitations of using heterogeneous data with the traditional EAV data model.");
-                });
-                ui.add_space(19.0);
-                ui.heading("COMING SOON");
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Request::Redock(tab) => {
+                self.undocked.retain(|undocked| undocked.tab != tab);
+                // A layout applied meanwhile may show the same tab again.
+                let tab = match self.pages.find_tab(&tab) {
+                    Some(_) => self.new_tab(tab.page),
+                    None => tab,
+                };
+                self.pages.push_to_focused_leaf(tab);
+            }
+            Request::ReopenClosed => {
+                if let Some(page) = self.recently_closed().into_iter().next() {
+                    self.visit(&page);
+                    self.open_page(page, Placement::Focused);
                 }
-                ui.heading("EEV Data Model");
-            },
-            |ui, state| {
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+            }
+            Request::ResetLayout => {
+                self.replace_layout(layout::default_layout(self.breakpoint, &self.viewer.pages), vec![]);
+            }
+            Request::UsePreset(preset) => {
+                let focused = self.focused_page().unwrap_or_else(Page::home);
+                let projects = self.viewer.state.graph.outgoing(&Page::new("portfolio")).iter()
+                    .filter(|page| page.project_id().is_some())
+                    .cloned()
+                    .collect();
+                self.replace_layout(preset.layout(&self.viewer.pages, focused, projects), vec![]);
+            }
+            Request::SetPinned { tab, pinned } => {
+                if pinned {
+                    self.viewer.state.pinned.insert(tab);
+                } else {
+                    self.viewer.state.pinned.remove(&tab);
                 }
-                if ui.link("Skills").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Skills));
+            }
+            Request::SaveGroup(name) => {
+                self.groups.retain(|group| group.name != name);
+                self.groups.push(TabGroup { name, dock: self.pages.clone(), undocked: self.undocked.clone() });
+            }
+            Request::RestoreGroup(name) => {
+                if let Some(group) = self.groups.iter().find(|group| group.name == name) {
+                    self.replace_layout(group.dock.clone(), group.undocked.clone());
                 }
-            },
-    );
-}
-
-fn work_history_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "work-history",
-            |ui, _state| {
-                ui.group(|ui| {
-                    ui.heading("IT Intern");
-                    ui.separator();
-                    ui.horizontal_wrapped(|ui| {
-                        ui.label("Florida Fish and Wildlife Conservation Commission (FWC)");
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label("Spring 2022 - Fall 2023");
-                        });
-                    });
-                    ui.add_space(13.0);
-                    ui.heading("Duties");
-                    ui.horizontal_wrapped(|ui| {
-                        ui.weak("◾");
-                        ui.label("Effectively maintained critical law enforcement IT infrastructure.");
-                    });
-                    ui.horizontal_wrapped(|ui| {
-                        ui.weak("◾");
-                        ui.label("Responded to internal IT-related concerns by staff.");
-                    });
-                    ui.horizontal_wrapped(|ui| {
-                        ui.weak("◾");
-                        ui.label("Acted and presented appropriately in a professional environment.");
-                    });
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+            }
+            Request::DeleteGroup(name) => self.groups.retain(|group| group.name != name),
+            Request::Back => {
+                let focused = self.focused_page();
+                if let Some(page) = self.viewer.state.history.back(focused) {
+                    self.open_page(page, Placement::Focused);
                 }
-                ui.heading("Work History");
-            },
-            |ui, state| {
-                if ui.link("Experience").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Experience));
+            }
+            Request::Forward => {
+                let focused = self.focused_page();
+                if let Some(page) = self.viewer.state.history.forward(focused) {
+                    self.open_page(page, Placement::Focused);
                 }
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+            }
+            #[cfg(target_arch = "wasm32")]
+            Request::BrowserNavigated(page) => {
+                // Hand-edited URLs can name any page.
+                if self.viewer.pages.contains(&page) {
+                    self.open_page(page, Placement::Focused);
+                } else {
+                    log::warn!("unknown page `{}` in the URL", page.id());
                 }
-            },
-    );
-}
+            }
+        }
+    }
 
-fn education_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "education",
-            |ui, _state| {
-                ui.group(|ui| {
-                    ui.heading("Florida State University");
-                    ui.separator();
-                    ui.horizontal_wrapped(|ui| {
-                        ui.label("Bachelor of Science, Information Technology");
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label("Expected 2025");
-                        });
-                    });
-                    ui.horizontal_wrapped(|ui| {
-                        ui.weak("GPA:");
-                        ui.label("3.99");
-                    });
-                });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
+    fn focused_tab(&mut self) -> Option<Tab> {
+        self.pages.find_active_focused().map(|(_, tab)| tab.clone())
+    }
+
+    fn focused_page(&mut self) -> Option<Page> {
+        self.focused_tab().map(|tab| tab.page)
+    }
+
+    /// Whether `page` has a tab in the dock or in a window of its own.
+    fn is_open(&self, page: &Page) -> bool {
+        self.docked_tab(page).is_some() || self.undocked.iter().any(|undocked| undocked.tab.page == *page)
+    }
+
+    /// The first docked tab of `page`.
+    fn docked_tab(&self, page: &Page) -> Option<Tab> {
+        self.pages.iter_all_tabs().map(|(_, tab)| tab).find(|tab| tab.page == *page).cloned()
+    }
+
+    /// A tab of `page` with an instance number no open tab has.
+    fn new_tab(&self, page: Page) -> Tab {
+        let docked = self.pages.iter_all_tabs().map(|(_, tab)| tab);
+        let undocked = self.undocked.iter().map(|undocked| &undocked.tab);
+        let instance = docked.chain(undocked)
+            .filter(|tab| tab.page == page)
+            .map(|tab| tab.instance + 1)
+            .max()
+            .unwrap_or(0);
+        Tab { page, instance }
+    }
+
+    /// Shows `dock` and `undocked` instead of the open tabs. The OS windows of undocked tabs are
+    /// replaced too, since they could show the same tabs as the new layout.
+    fn replace_layout(&mut self, dock: egui_dock::DockState<Tab>, undocked: Vec<Undocked>) {
+        let undocked: Vec<Undocked> = undocked.into_iter()
+            .map(|undocked| Undocked { created: false, ..undocked })
+            .collect();
+        for old in std::mem::replace(&mut self.undocked, undocked) {
+            if !self.undocked.iter().any(|undocked| undocked.tab == old.tab) {
+                self.viewer.state.remember_closed(old.tab.page);
+            }
+        }
+        self.set_dock(dock);
+    }
+
+    /// Shows `dock` in place of the docked tabs, reopening the pinned tabs it doesn't show.
+    fn set_dock(&mut self, mut dock: egui_dock::DockState<Tab>) {
+        // Layouts from presets, storage and links come with the default labels.
+        dock.translations.tab_context_menu.eject_button = "Move to new window".to_owned();
+        self.pages = dock;
+        self.keep_pinned();
+    }
+
+    /// Reopens the pinned tabs a new layout doesn't show.
+    fn keep_pinned(&mut self) {
+        for tab in self.viewer.state.pinned.clone() {
+            let undocked = self.undocked.iter().any(|undocked| undocked.tab == tab);
+            if !undocked && self.pages.find_tab(&tab).is_none() {
+                self.pages.push_to_first_leaf(tab);
+            }
+        }
+    }
+
+    /// Closes the first tab of `page`, preferring an undocked one.
+    fn close_page(&mut self, page: &Page) {
+        let undocked = self.undocked.iter().find(|undocked| undocked.tab.page == *page);
+        if let Some(tab) = undocked.map(|undocked| undocked.tab.clone()).or_else(|| self.docked_tab(page)) {
+            self.close_tab(&tab);
+        }
+    }
+
+    fn close_tab(&mut self, tab: &Tab) {
+        if self.viewer.state.pinned.contains(tab) {
+            return;
+        }
+        if let Some(i) = self.undocked.iter().position(|undocked| undocked.tab == *tab) {
+            self.undocked.remove(i);
+            self.viewer.state.remember_closed(tab.page.clone());
+            return;
+        }
+        if self.viewer.keep_last_tab && self.pages.iter_all_tabs().count() <= 1 {
+            return;
+        }
+        if let Some(ids) = self.pages.find_tab(tab) {
+            self.pages.remove_tab(ids);
+            self.viewer.state.remember_closed(tab.page.clone());
+        }
+    }
+
+    /// Closed pages that aren't open again, most recently closed first.
+    fn recently_closed(&self) -> Vec<Page> {
+        let closed = self.viewer.state.closed.iter().rev();
+        closed.filter(|page| !self.is_open(page)).cloned().collect()
+    }
+
+    /// The tabs in the leaf showing `tab`.
+    fn leaf_tabs(&self, tab: &Tab) -> Vec<Tab> {
+        let Some((surface, node, _)) = self.pages.find_tab(tab) else {
+            return vec![];
+        };
+        self.pages[surface][node].tabs().map_or(vec![], <[Tab]>::to_vec)
+    }
+
+    /// Records leaving the focused page for `page` in the navigation history.
+    fn visit(&mut self, page: &Page) {
+        if let Some(focused) = self.focused_page().filter(|focused| focused != page) {
+            self.viewer.state.history.visit(focused);
+        }
+    }
+
+    /// Focuses the first tab of `page`, see [`Resume::focus_tab`]. Returns the tab, or `None` if
+    /// the page isn't open.
+    fn focus_page(&mut self, page: &Page) -> Option<Tab> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(undocked) = self.undocked.iter().find(|undocked| undocked.tab.page == *page) {
+            self.focus_undocked = Some(undocked.tab.clone());
+            return Some(undocked.tab.clone());
+        }
+        let tab = self.docked_tab(page)?;
+        self.focus_tab(&tab);
+        Some(tab)
+    }
+
+    /// Makes `tab` active and focused. Returns false if it isn't docked.
+    fn focus_tab(&mut self, tab: &Tab) -> bool {
+        let Some(ids) = self.pages.find_tab(tab) else {
+            return false;
+        };
+        self.pages.set_active_tab(ids);
+        self.pages.set_focused_node_and_surface((ids.0, ids.1));
+        true
+    }
+
+    /// Flashes `tab`, to show where a link opened a page that was already open.
+    fn highlight(&mut self, tab: Tab) {
+        self.viewer.highlight_tab = Some((tab, self.viewer.now));
+    }
+
+    /// Focuses `page` if it's already open, otherwise opens it at `placement`. Returns the tab of
+    /// a page that was already open.
+    fn open_page(&mut self, page: Page, placement: Placement) -> Option<Tab> {
+        if let Some(tab) = self.focus_page(&page) {
+            return Some(tab);
+        }
+        let tab = self.new_tab(page);
+        match placement {
+            Placement::Focused => self.pages.push_to_focused_leaf(tab),
+            Placement::Split => self.split_page(tab, egui_dock::Split::Right),
+            Placement::Window => {
+                let surface = self.pages.add_window(vec![tab]);
+                self.pages.set_focused_node_and_surface((surface, egui_dock::NodeIndex::root()));
+            }
+        }
+        None
+    }
+
+    /// Opens `tab` in a new split of the focused leaf.
+    fn split_page(&mut self, tab: Tab, direction: egui_dock::Split) {
+        let Some(leaf) = self.pages.focused_leaf() else {
+            self.pages.push_to_focused_leaf(tab);
+            return;
+        };
+        let [_, new] = self.pages.split(leaf, direction, 0.5, egui_dock::Node::leaf(tab));
+        self.pages.set_focused_node_and_surface((leaf.0, new));
+    }
+
+    /// Adds an observer after the ones already added, see [`RequestObserver`].
+    pub fn add_observer(&mut self, observer: impl RequestObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Adds a page at startup, in addition to the pages described by the content file.
+    pub fn register_page(&mut self, page: Page, entry: impl PageEntry + 'static) {
+        self.viewer.pages.register(page, entry);
+        self.viewer.state.targets = self.viewer.pages.link_targets();
+        self.viewer.state.graph = LinkGraph::new(&self.viewer.pages, &self.viewer.state.targets);
+    }
+}
+
+/// Registers every page described by `content`.
+fn content_pages(content: Content) -> Registry {
+    let mut registry = Registry::default();
+    let content = Rc::new(content);
+    ContentPage::register_all(&mut registry, &content);
+    ProjectPage::register_all(&mut registry, &content);
+    registry
+}
+
+#[derive(Default)]
+pub struct State {
+    requests: Vec<Request>,
+    history: History,
+    /// Closed pages, the most recently closed last.
+    closed: Vec<Page>,
+    /// Tabs that can't be closed, and stay open when the layout is replaced. Duplicates of a
+    /// pinned tab aren't pinned themselves.
+    pinned: BTreeSet<Tab>,
+    /// The page and section anchor of the last [`Request::ScrollTo`], until the page is shown.
+    scroll_to: Option<(Page, String)>,
+    /// Internal link targets, see [`Registry::link_targets`].
+    targets: BTreeMap<String, Page>,
+    graph: LinkGraph,
+}
+
+impl State {
+    /// Finds the page a `[[target]]` link points to.
+    pub fn resolve(&self, target: &str) -> Option<Page> {
+        self.targets.get(&pages::link_key(target)).cloned()
+    }
+
+    pub fn remember_closed(&mut self, page: Page) {
+        const MAX_CLOSED: usize = 10;
+        self.closed.retain(|closed| *closed != page);
+        self.closed.push(page);
+        if self.closed.len() > MAX_CLOSED {
+            self.closed.remove(0);
+        }
+    }
+
+    /// Whether the section with the `anchor` key on `page` should scroll into view.
+    pub fn scrolls_to(&self, page: &Page, anchor: &str) -> bool {
+        self.scroll_to.as_ref().is_some_and(|(to, to_anchor)| to == page && to_anchor == anchor)
+    }
+}
+
+/// The pages focused before and after the current one, for back and forward navigation.
+#[derive(Default)]
+pub struct History {
+    back: Vec<Page>,
+    forward: Vec<Page>,
+}
+
+impl History {
+    /// Records leaving `from` for a newly opened page, which drops the pages ahead.
+    pub fn visit(&mut self, from: Page) {
+        self.back.push(from);
+        self.forward.clear();
+    }
+
+    /// Steps back from `current`, returning the page to open.
+    pub fn back(&mut self, current: Option<Page>) -> Option<Page> {
+        let page = self.back.pop()?;
+        self.forward.extend(current);
+        Some(page)
+    }
+
+    /// Steps forward from `current`, returning the page to open.
+    pub fn forward(&mut self, current: Option<Page>) -> Option<Page> {
+        let page = self.forward.pop()?;
+        self.back.extend(current);
+        Some(page)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+pub struct PageViewer {
+    state: State,
+    pages: Registry,
+    /// The tab flashing to draw attention to it, and when it started.
+    highlight_tab: Option<(Tab, f64)>,
+    /// Time of the current frame, see [`egui::InputState::time`].
+    now: f64,
+    open_tabs: usize,
+    /// Stops the last open tab from being closed, see [`Content::keep_last_tab`].
+    keep_last_tab: bool,
+}
+
+/// How long a highlighted page flashes.
+const HIGHLIGHT_SECONDS: f64 = 0.9;
+/// The yellow of the classic "yellow fade" highlight.
+const HIGHLIGHT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 214, 92);
+
+impl PageViewer {
+    /// How strongly `tab` is highlighted right now: two pulses fading out, from 0 to 1.
+    fn highlight(&self, tab: &Tab) -> f32 {
+        match &self.highlight_tab {
+            Some((highlighted, start)) if highlighted == tab => {
+                let t = ((self.now - start) / HIGHLIGHT_SECONDS).clamp(0.0, 1.0) as f32;
+                (1.0 - t) * (std::f32::consts::TAU * t).sin().powi(2)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The page title, numbered from the second instance on and marked when pinned.
+    fn tab_title(&self, tab: &Tab) -> String {
+        let mut title = self.pages.title(&tab.page);
+        if tab.instance > 0 {
+            title = format!("{title} ({})", tab.instance + 1);
+        }
+        match self.state.pinned.contains(tab) {
+            true => format!("📌 {title}"),
+            false => title,
+        }
+    }
+}
+
+fn blend(from: egui::Color32, to: egui::Color32, t: f32) -> egui::Color32 {
+    (egui::Rgba::from(from) * (1.0 - t) + egui::Rgba::from(to) * t).into()
+}
+
+impl egui_dock::TabViewer for PageViewer {
+    type Tab = Tab;
+    
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        self.tab_title(tab).into()
+    }
+
+    /// Keyed by the tab instead of its title, which changes when pinning.
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        egui::Id::new(&*tab)
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
+        self.pages.show(ui, &mut self.state, tab);
+        let highlight = self.highlight(tab);
+        if highlight > 0.0 {
+            ui.painter().rect_filled(ui.max_rect(), 0.0, HIGHLIGHT_COLOR.gamma_multiply(0.3 * highlight));
+        }
+    }
+
+    fn closeable(&mut self, tab: &mut Self::Tab) -> bool {
+        !self.state.pinned.contains(tab) && (!self.keep_last_tab || self.open_tabs > 1)
+    }
+
+    fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
+        self.state.remember_closed(tab.page.clone());
+        true
+    }
+
+    fn context_menu(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab, _: SurfaceIndex, _: NodeIndex) {
+        let requests = [
+            ("Close others", Request::CloseOthers(tab.clone())),
+            ("Close to the right", Request::CloseToTheRight(tab.clone())),
+            ("Split right", Request::MoveToSplit { tab: tab.clone(), direction: egui_dock::Split::Right }),
+            ("Split down", Request::MoveToSplit { tab: tab.clone(), direction: egui_dock::Split::Below }),
+            ("Duplicate", Request::Duplicate(tab.clone())),
+        ];
+        for (label, request) in requests {
+            if ui.button(label).clicked() {
+                self.state.requests.push(request);
+                ui.close_menu();
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Move to OS window").clicked() {
+            self.state.requests.push(Request::Undock(tab.clone()));
+            ui.close_menu();
+        }
+        let pinned = self.state.pinned.contains(tab);
+        if ui.button(if pinned { "Unpin" } else { "Pin" }).clicked() {
+            self.state.requests.push(Request::SetPinned { tab: tab.clone(), pinned: !pinned });
+            ui.close_menu();
+        }
+        if ui.button("Copy link to page").clicked() {
+            ui.ctx().copy_text(layout::page_url(&tab.page));
+            ui.close_menu();
+        }
+        // egui_dock adds the buttons to move the tab to a window and to close it.
+        ui.separator();
+    }
+
+    fn tab_style_override(&self, tab: &Self::Tab, global_style: &TabStyle) -> Option<TabStyle> {
+        let mut style = global_style.clone();
+        style.tab_body.inner_margin = 13.0.into();
+        let highlight = self.highlight(tab);
+        if highlight > 0.0 {
+            for interaction in [&mut style.active, &mut style.focused, &mut style.inactive, &mut style.hovered] {
+                interaction.bg_fill = blend(interaction.bg_fill, HIGHLIGHT_COLOR, highlight);
+            }
+        }
+        Some(style)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Request {
+    OpenPage(Page, Placement),
+    /// Opens a page in a new split of the focused leaf, or focuses it if it's already open.
+    OpenInSplit { page: Page, direction: egui_dock::Split },
+    /// Focuses the first tab of a page if it's open.
+    FocusPage(Page),
+    /// Focuses a tab if it's open.
+    FocusTab(Tab),
+    /// Closes the first tab of a page if it's open.
+    ClosePage(Page),
+    /// Closes a tab, leaving other instances of its page open.
+    CloseTab(Tab),
+    /// Closes the other tabs in the leaf of a tab.
+    CloseOthers(Tab),
+    /// Closes the tabs after a tab in its leaf.
+    CloseToTheRight(Tab),
+    /// Moves a tab out of its leaf into a new split of the leaf.
+    MoveToSplit { tab: Tab, direction: egui_dock::Split },
+    /// Opens another instance of a tab's page next to it, with its own scroll position.
+    Duplicate(Tab),
+    /// Opens a page like [`Request::OpenPage`] and scrolls to the section or markdown heading that
+    /// matches the anchor, see [`pages::link_key`].
+    ScrollTo { page: Page, anchor: String, placement: Placement },
+    /// Moves a tab out of the dock into its own OS window.
+    #[cfg(not(target_arch = "wasm32"))]
+    Undock(Tab),
+    /// Moves an undocked tab back into the dock.
+    #[cfg(not(target_arch = "wasm32"))]
+    Redock(Tab),
+    /// Reopens the most recently closed page.
+    ReopenClosed,
+    /// Replaces all tabs with the default layout.
+    ResetLayout,
+    UsePreset(Preset),
+    /// Pins or unpins a tab, see [`State::pinned`].
+    SetPinned { tab: Tab, pinned: bool },
+    /// Saves the open tabs as a group, replacing any group of the same name.
+    SaveGroup(String),
+    /// Replaces all tabs with the ones of a group.
+    RestoreGroup(String),
+    DeleteGroup(String),
+    /// Refocuses the page focused before the last navigation. The browser's back and forward
+    /// buttons keep their own history and leave this one alone, so going back in the browser
+    /// doesn't drop the pages ahead in the app.
+    Back,
+    Forward,
+    /// Opens the page the browser's back or forward button went to, without recording it in
+    /// [`State::history`].
+    #[cfg(target_arch = "wasm32")]
+    BrowserNavigated(Page),
+}
+
+impl Request {
+    /// Whether the request opens or focuses a page, which the web build records as a new browser
+    /// history entry.
+    #[cfg(target_arch = "wasm32")]
+    fn navigates(&self) -> bool {
+        matches!(
+            self,
+            Request::OpenPage(..)
+                | Request::OpenInSplit { .. }
+                | Request::FocusPage(_)
+                | Request::FocusTab(_)
+                | Request::ScrollTo { .. }
+                | Request::ReopenClosed
+        )
+    }
+}
+
+/// Sees every request before and after [`Resume`] handles it, in the order the observers were
+/// added.
+pub trait RequestObserver {
+    /// Passes the request on to the next observer, possibly rewritten, or drops it by returning
+    /// `None`.
+    fn before(&mut self, request: Request) -> Option<Request> {
+        Some(request)
+    }
+
+    /// Called once the request was handled. Pushed `requests` are handled in the next frame.
+    fn after(&mut self, _request: &Request, _requests: &mut Vec<Request>) {}
+}
+
+/// Logs every request at debug level.
+pub struct LogRequests;
+
+impl RequestObserver for LogRequests {
+    fn after(&mut self, request: &Request, _: &mut Vec<Request>) {
+        log::debug!("handled {request:?}");
+    }
+}
+
+/// Where [`Request::OpenPage`] opens a page that isn't open yet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Placement {
+    /// A new tab in the focused leaf.
+    Focused,
+    /// A new split beside the focused leaf.
+    Split,
+    /// A new floating window.
+    Window,
+}
+
+impl Placement {
+    /// Where a click on a link opens its page: Ctrl+click and middle-click open a split, and
+    /// Shift+click a window. `None` if the link wasn't clicked.
+    pub fn of_click(response: &egui::Response) -> Option<Self> {
+        if response.middle_clicked() {
+            return Some(Placement::Split);
+        }
+        if !response.clicked() {
+            return None;
+        }
+        let modifiers = response.ctx.input(|input| input.modifiers);
+        Some(if modifiers.command {
+            Placement::Split
+        } else if modifiers.shift {
+            Placement::Window
+        } else {
+            Placement::Focused
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// A page described by the resume content file.
+pub struct ContentPage {
+    page: Page,
+    content: Rc<Content>,
+}
+
+impl ContentPage {
+    /// Registers every page described by `content`.
+    pub fn register_all(pages: &mut Registry, content: &Rc<Content>) {
+        for def in &content.pages {
+            pages.register(def.id.clone(), ContentPage {
+                page: def.id.clone(),
+                content: content.clone(),
+            });
+        }
+    }
+
+    fn def(&self) -> &PageContent {
+        self.content.page(&self.page).expect("registered pages have content")
+    }
+}
+
+impl PageEntry for ContentPage {
+    fn title(&self) -> String {
+        self.def().title.clone()
+    }
+
+    fn header(&self, ui: &mut egui::Ui, state: &mut State) {
+        let def = self.def();
+        if self.page != Page::home() {
+            ui.visuals_mut().button_frame = false;
+            if let Some(placement) = Placement::of_click(&ui.button("🏠")) {
+                state.requests.push(Request::OpenPage(Page::home(), placement));
+            }
+        }
+        ui.heading(def.heading.as_deref().unwrap_or(&def.title));
+    }
+
+    fn body(&self, ui: &mut egui::Ui, state: &mut State) {
+        let (def, content) = (self.def(), self.content.as_ref());
+        ui.style_mut().spacing.item_spacing = egui::vec2(3.0, 7.0);
+        if let Some(note) = &def.note {
+            ui.weak(note);
+            ui.add_space(19.0);
+        }
+        for (i, section) in def.sections.iter().enumerate() {
+            if i > 0 {
+                ui.add_space(19.0);
+            }
+            section_ui(ui, state, &self.page, section);
+        }
+        match def.kind {
+            PageKind::Text => {}
+            PageKind::Home => home_body(ui, state, content),
+            PageKind::Contact => contact_body(ui, content),
+            PageKind::Portfolio => portfolio_body(ui, state, content),
+            PageKind::WorkHistory => work_history_body(ui, content),
+            PageKind::Education => education_body(ui, content),
+        }
+    }
+
+    fn links(&self) -> Vec<String> {
+        let def = self.def();
+        let mut links: Vec<String> = def.sections.iter()
+            .filter_map(|section| section.link.as_ref())
+            .map(|page| page.id().to_owned())
+            .collect();
+        links.extend(def.wiki_links().into_iter().map(str::to_owned));
+        match def.kind {
+            PageKind::Home => {
+                links.extend(self.content.tree.iter().map(|entry| entry.page.id().to_owned()));
+            }
+            PageKind::Portfolio => {
+                let listed = self.content.projects.iter().filter(|p| p.listed);
+                links.extend(listed.map(|p| Page::project(&p.id).id().to_owned()));
+            }
+            _ => {}
+        }
+        links
+    }
+
+    fn related(&self) -> Option<Vec<Page>> {
+        self.def().related.clone()
+    }
+}
+
+fn home_body(ui: &mut egui::Ui, state: &mut State, content: &Content) {
+    ui.separator();
+    ui.heading("Explore");
+    ui.add_space(19.0);
+    for entry in &content.tree {
+        tree_item(ui, state, entry.page.clone(), content.title(&entry.page), entry.level);
+    }
+    if let Some(updated) = &content.updated {
+        ui.add_space(19.0);
+        ui.weak(format!("Last Updated: {updated}"));
+    }
+}
+
+fn contact_body(ui: &mut egui::Ui, content: &Content) {
+    let person = &content.person;
+    ui.style_mut().spacing.item_spacing = egui::vec2(7.0, 7.0);
+    ui.heading(&person.name);
+    ui.separator();
+    if let Some(email) = &person.email {
+        ui.horizontal_wrapped(|ui| {
+            ui.strong("Email:");
+            ui.label(email);
+        });
+    }
+    for link in &person.links {
+        ui.hyperlink_to(&link.label, &link.url);
+    }
+    if let Some(photo) = &person.photo {
+        ui.separator();
+        let resp = ui.add(egui::Image::new(&photo.url).rounding(11.0));
+        if let Some(description) = &photo.description {
+            resp.on_hover_text(description);
+        }
+    }
+}
+
+fn portfolio_body(ui: &mut egui::Ui, state: &mut State, content: &Content) {
+    for (i, project) in content.projects.iter().filter(|p| p.listed).enumerate() {
+        if i > 0 {
+            ui.add_space(19.0);
+        }
+        page_object(ui, state, Some(Page::project(&project.id)), &project.title, |ui, _state| {
+            ui.label(&project.summary);
+            ui.weak("Click `Learn More` for details");
+        });
+    }
+}
+
+/// The page of a single [`Project`] record, registered as `portfolio/<project id>`.
+pub struct ProjectPage {
+    project: String,
+    content: Rc<Content>,
+}
+
+impl ProjectPage {
+    /// Registers a page for every project in `content`, listed or not.
+    pub fn register_all(pages: &mut Registry, content: &Rc<Content>) {
+        for project in &content.projects {
+            pages.register(Page::project(&project.id), ProjectPage {
+                project: project.id.clone(),
+                content: content.clone(),
+            });
+        }
+    }
+
+    fn project(&self) -> &Project {
+        self.content.projects.iter()
+            .find(|p| p.id == self.project)
+            .expect("registered projects have content")
+    }
+}
+
+impl PageEntry for ProjectPage {
+    fn title(&self) -> String {
+        self.project().title.clone()
+    }
+
+    fn body(&self, ui: &mut egui::Ui, _state: &mut State) {
+        let project = self.project();
+        ui.style_mut().spacing.item_spacing = egui::vec2(3.0, 7.0);
+        ui.horizontal_wrapped(|ui| {
+            ui.weak("Role:");
+            ui.label(&project.role);
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.weak("Status:");
+            match project.status {
+                Status::InProgress => ui.colored_label(egui::Color32::LIGHT_GREEN, "IN PROGRESS"),
+                Status::UnderReview => ui.colored_label(egui::Color32::YELLOW, "UNDER REVIEW"),
+                Status::Complete => ui.colored_label(egui::Color32::LIGHT_BLUE, "COMPLETE"),
+            };
+        });
+        if let Some(dates) = &project.dates {
+            ui.horizontal_wrapped(|ui| {
+                ui.weak("Dates:");
+                ui.label(dates);
+            });
+        }
+        if !project.tech.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.weak("Tech Stack:");
+                ui.label(project.tech.join(", "));
+            });
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.weak("Overview:");
+            ui.label(&project.overview);
+        });
+        if !project.links.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.weak("Links:");
+                for link in &project.links {
+                    ui.hyperlink_to(&link.label, &link.url);
                 }
-                ui.heading("Education");
-            },
-            |ui, state| {
-                if ui.link("Experience").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Experience));
+            });
+        }
+        ui.add_space(19.0);
+        if project.gallery.is_empty() {
+            ui.heading("COMING SOON");
+            return;
+        }
+        ui.heading("Showcase:");
+        ui.vertical_centered(|ui| {
+            for image in &project.gallery {
+                let resp = ui.add(egui::Image::new(&image.url).rounding(11.0));
+                if let Some(description) = &image.description {
+                    resp.on_hover_text(description);
                 }
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
+                if let Some(caption) = &image.caption {
+                    ui.weak(caption);
                 }
-            },
-    );
+                ui.hyperlink_to("Click here to view a full-size version of the image", &image.url);
+            }
+        });
+    }
+
+    fn related(&self) -> Option<Vec<Page>> {
+        self.project().related.clone()
+    }
 }
 
-fn goals_page(ui: &mut egui::Ui, state: &mut State) {
-    page_ui(ui, state, "goals",
-            |ui, _state| {
-                ui.heading("Career Goals");
-                ui.label("My ultimate goal is independence. I know that may not be what a potential employer is looking for, but it's true. I'd like to eventually see myself working full time on my own projects with little to no oversight.");
-                ui.label("I'm also genuinely interested in learning. I'd say that I am extremely self-motivated and capable of gaining an in-depth understanding of anything I find interesting. And just about anything an employer looking for an IT guy needs is something I find interesting.");
-                ui.add_space(19.0);
-                ui.heading("Quick List");
-                ui.horizontal_wrapped(|ui| {
-                    ui.weak("◾");
-                    ui.label("Master computing in all forms (networking, software development, hardware, infrastructure, etc.).");
+fn work_history_body(ui: &mut egui::Ui, content: &Content) {
+    for (i, job) in content.jobs.iter().enumerate() {
+        if i > 0 {
+            ui.add_space(19.0);
+        }
+        ui.group(|ui| {
+            ui.heading(&job.title);
+            ui.separator();
+            ui.horizontal_wrapped(|ui| {
+                ui.label(&job.organization);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(&job.dates);
+                });
+            });
+            if !job.duties.is_empty() {
+                ui.add_space(13.0);
+                ui.heading("Duties");
+                bullets_ui(ui, &job.duties);
+            }
+        });
+    }
+}
+
+fn education_body(ui: &mut egui::Ui, content: &Content) {
+    for (i, school) in content.schools.iter().enumerate() {
+        if i > 0 {
+            ui.add_space(19.0);
+        }
+        ui.group(|ui| {
+            ui.heading(&school.name);
+            ui.separator();
+            ui.horizontal_wrapped(|ui| {
+                ui.label(&school.degree);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(&school.dates);
                 });
+            });
+            if let Some(gpa) = &school.gpa {
                 ui.horizontal_wrapped(|ui| {
-                    ui.weak("◾");
-                    ui.label("Work on exciting projects related to computing that I genuinely believe in.");
+                    ui.weak("GPA:");
+                    ui.label(gpa);
                 });
-            },
-            |ui, state| {
-                ui.visuals_mut().button_frame = false;
-                if ui.button("🏠").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Home));
-                }
-                ui.heading("Goals");
-            },
-            |ui, state| {
-                if ui.link("Skills").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Skills));
-                }
-                if ui.link("Portfolio").clicked() {
-                    state.requests.push(Request::OpenPage(Page::Portfolio));
-                }
-            },
-    );
+            }
+        });
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -762,8 +1576,8 @@ fn tree_item(ui: &mut egui::Ui, state: &mut State, page: Page, title: &str, leve
                 ui.add_space(ui.spacing().indent);
             }
             ui.label("▪");
-            if ui.link(title).clicked() {
-                state.requests.push(Request::OpenPage(page));
+            if let Some(placement) = Placement::of_click(&ui.link(title)) {
+                state.requests.push(Request::OpenPage(page, placement));
             }
         }).response
     });
@@ -782,10 +1596,8 @@ fn page_object(
             ui.heading(title);
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 let resp = ui.add_enabled(page.is_some(), egui::Link::new("Learn More"));
-                if resp.clicked() {
-                    if let Some(page) = page {
-                        state.requests.push(Request::OpenPage(page));
-                    }
+                if let (Some(placement), Some(page)) = (Placement::of_click(&resp), page) {
+                    state.requests.push(Request::OpenPage(page, placement));
                 }
             });
         });
@@ -794,4 +1606,325 @@ fn page_object(
     });
 }
 
+fn section_ui(ui: &mut egui::Ui, state: &mut State, page: &Page, section: &Section) {
+    let body = |ui: &mut egui::Ui, state: &mut State| {
+        if let Some(heading) = &section.heading {
+            let anchor = pages::link_key(heading);
+            let heading = egui::RichText::new(heading).heading();
+            let resp = match &section.link {
+                Some(page) => {
+                    let resp = ui.link(heading);
+                    if let Some(placement) = Placement::of_click(&resp) {
+                        state.requests.push(Request::OpenPage(page.clone(), placement));
+                    }
+                    resp
+                }
+                None => ui.label(heading),
+            };
+            if state.scrolls_to(page, &anchor) {
+                resp.scroll_to_me(Some(egui::Align::TOP));
+            }
+            if section.boxed {
+                ui.separator();
+            }
+        }
+        for paragraph in &section.text {
+            paragraph_ui(ui, state, paragraph);
+        }
+        if let Some(markdown) = &section.markdown {
+            markdown_ui(ui, state, page, markdown);
+        }
+    };
+    if section.boxed {
+        ui.group(|ui| body(ui, state));
+    } else {
+        body(ui, state);
+    }
+}
+
+fn paragraph_ui(ui: &mut egui::Ui, state: &mut State, paragraph: &Paragraph) {
+    let mut rich = RichParagraph::new();
+    match paragraph {
+        Paragraph::Plain(text) => push_text(ui, state, &mut rich, text, TextStyle::Normal),
+        Paragraph::Styled(span) => push_span(ui, state, &mut rich, span),
+        Paragraph::Spans(spans) => {
+            for span in spans {
+                push_span(ui, state, &mut rich, span);
+            }
+        }
+    }
+    let shown = rich.show(ui);
+    if let Some(link) = shown.inner {
+        follow_link(state, link, &shown.response);
+    }
+}
+
+fn push_span(ui: &egui::Ui, state: &State, rich: &mut RichParagraph<Link>, span: &Span) {
+    match span {
+        Span::Plain(text) => push_text(ui, state, rich, text, TextStyle::Normal),
+        Span::Styled { text, style } => push_text(ui, state, rich, text, *style),
+    }
+}
+
+fn push_text(ui: &egui::Ui, state: &State, rich: &mut RichParagraph<Link>, text: &str, style: TextStyle) {
+    let styled = |text: &str| {
+        let text = egui::RichText::new(text);
+        match style {
+            TextStyle::Normal => text,
+            TextStyle::Weak => text.weak(),
+            TextStyle::Strong => text.strong(),
+        }
+    };
+    for piece in split_wiki_links(text) {
+        match piece {
+            Piece::Text(text) => rich.text(styled(text)),
+            Piece::WikiLink { target, label } => push_wiki_link(ui, state, rich, target, styled(label)),
+        }
+    }
+}
+
+/// Where a link in a [`RichParagraph`] goes.
+#[derive(Clone, Debug)]
+pub enum Link {
+    Page(Page),
+    /// A section of a page, from `[[target#anchor]]`.
+    Anchor(Page, String),
+    Url(String),
+}
+
+/// Opens the link clicked in `response`, see [`Placement::of_click`].
+fn follow_link(state: &mut State, link: Link, response: &egui::Response) {
+    match link {
+        Link::Page(page) => {
+            if let Some(placement) = Placement::of_click(response) {
+                state.requests.push(Request::OpenPage(page, placement));
+            }
+        }
+        Link::Anchor(page, anchor) => {
+            if let Some(placement) = Placement::of_click(response) {
+                state.requests.push(Request::ScrollTo { page, anchor, placement });
+            }
+        }
+        Link::Url(url) => response.ctx.open_url(egui::OpenUrl::new_tab(url)),
+    }
+}
+
+/// Adds a link to the page `target` resolves to, or an error if there is no such page.
+fn push_wiki_link(
+    ui: &egui::Ui,
+    state: &State,
+    rich: &mut RichParagraph<Link>,
+    target: &str,
+    text: egui::RichText,
+) {
+    match state.resolve(target) {
+        Some(page) => match target.split_once('#') {
+            Some((_, anchor)) => rich.link(text, Link::Anchor(page, anchor.to_owned())),
+            None => rich.link(text, Link::Page(page)),
+        },
+        None => rich.annotated(
+            text.color(ui.visuals().error_fg_color),
+            format!("Unknown page `{target}`"),
+        ),
+    }
+}
+
+fn bullets_ui(ui: &mut egui::Ui, bullets: &[String]) {
+    for bullet in bullets {
+        ui.horizontal_wrapped(|ui| {
+            ui.weak("◾");
+            ui.label(bullet);
+        });
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui_dock::TabViewer;
+
+    fn resume(text: &str) -> Resume {
+        Resume::load(&egui::Context::default(), None, Content::from_toml(text).unwrap())
+    }
+
+    #[test]
+    fn history_steps_back_and_forward() {
+        let [a, b, c] = ["a", "b", "c"].map(Page::new);
+        let mut history = History::default();
+        assert!(!history.can_go_back());
+        history.visit(a.clone());
+        history.visit(b.clone());
+        // From `c`, back to `b` and `a`, then forward to `b` and `c`.
+        assert_eq!(history.back(Some(c.clone())), Some(b.clone()));
+        assert_eq!(history.back(Some(b.clone())), Some(a.clone()));
+        assert_eq!(history.back(Some(a.clone())), None);
+        assert_eq!(history.forward(Some(a.clone())), Some(b.clone()));
+        assert_eq!(history.forward(Some(b.clone())), Some(c.clone()));
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(Some(c)), Some(b));
+        assert_eq!(history.back(None), Some(a));
+    }
+
+    #[test]
+    fn visiting_drops_the_pages_ahead() {
+        let [a, b, c] = ["a", "b", "c"].map(Page::new);
+        let mut history = History::default();
+        history.visit(a.clone());
+        assert_eq!(history.back(Some(b.clone())), Some(a.clone()));
+        assert!(history.can_go_forward());
+        history.visit(a.clone());
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(Some(c.clone())), Some(a));
+        assert_eq!(history.forward(None), Some(c));
+    }
+
+    #[test]
+    fn last_tab_is_kept_when_the_content_asks() {
+        for keep in [false, true] {
+            let setting = format!("keep_last_tab = {keep}");
+            let mut resume = resume(&content::DEFAULT_CONTENT.replace("keep_last_tab = false", &setting));
+            let mut tabs: Vec<Tab> = resume.pages.iter_all_tabs().map(|(_, tab)| tab.clone()).collect();
+            assert!(tabs.len() > 1);
+            resume.viewer.open_tabs = tabs.len();
+            assert!(resume.viewer.closeable(&mut tabs[0]));
+
+            resume.viewer.state.requests.extend(tabs.iter().cloned().map(Request::CloseTab));
+            resume.drain_requests();
+            let mut left: Vec<Tab> = resume.pages.iter_all_tabs().map(|(_, tab)| tab.clone()).collect();
+            assert_eq!(left.len(), usize::from(keep), "keep_last_tab = {keep}");
+            if let Some(last) = left.first_mut() {
+                resume.viewer.open_tabs = 1;
+                assert!(!resume.viewer.closeable(last));
+            }
+        }
+    }
+
+    /// Keeps the home page open and opens the education page after the experience page.
+    struct KeepHome;
+
+    impl RequestObserver for KeepHome {
+        fn before(&mut self, request: Request) -> Option<Request> {
+            match request {
+                Request::ClosePage(page) if page == Page::home() => None,
+                request => Some(request),
+            }
+        }
+
+        fn after(&mut self, request: &Request, requests: &mut Vec<Request>) {
+            if matches!(request, Request::OpenPage(page, _) if *page == Page::new("experience")) {
+                requests.push(Request::OpenPage(Page::new("education"), Placement::Focused));
+            }
+        }
+    }
+
+    #[test]
+    fn observers_drop_and_queue_requests() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        resume.add_observer(KeepHome);
+        let home = Tab::from(Page::home());
+        assert!(resume.pages.find_tab(&home).is_some());
+        resume.viewer.state.requests = vec![
+            Request::ClosePage(Page::home()),
+            Request::OpenPage(Page::new("experience"), Placement::Focused),
+        ];
+        resume.drain_requests();
+        assert!(resume.pages.find_tab(&home).is_some());
+        assert!(resume.docked_tab(&Page::new("experience")).is_some());
+        // Queued requests wait for the next frame.
+        assert!(matches!(
+            &resume.viewer.state.requests[..],
+            [Request::OpenPage(page, Placement::Focused)] if *page == Page::new("education"),
+        ));
+        assert!(resume.docked_tab(&Page::new("education")).is_none());
+        resume.drain_requests();
+        assert!(resume.docked_tab(&Page::new("education")).is_some());
+    }
+
+    /// A page added in code, linking to the skills page.
+    struct Guestbook;
+
+    impl PageEntry for Guestbook {
+        fn title(&self) -> String {
+            "Guestbook".to_owned()
+        }
+
+        fn body(&self, ui: &mut egui::Ui, _: &mut State) {
+            ui.label("Thanks for visiting!");
+        }
+
+        fn links(&self) -> Vec<String> {
+            vec!["skills".to_owned()]
+        }
+    }
+
+    #[test]
+    fn registered_pages_open_and_link_like_content_pages() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        let guestbook = Page::new("guestbook");
+        resume.register_page(guestbook.clone(), Guestbook);
+        assert_eq!(resume.viewer.tab_title(&Tab::from(guestbook.clone())), "Guestbook");
+        // Links resolve to the new page by title, and it shows up in the backlinks of its links.
+        assert_eq!(resume.viewer.state.targets.get(&pages::link_key("Guestbook")), Some(&guestbook));
+        assert!(resume.viewer.state.graph.backlinks(&Page::new("skills")).contains(&guestbook));
+
+        resume.viewer.state.requests.push(Request::OpenPage(guestbook.clone(), Placement::Focused));
+        resume.drain_requests();
+        assert!(resume.docked_tab(&guestbook).is_some());
+    }
+
+    #[test]
+    fn scrolling_to_a_section_opens_its_page_at_the_placement() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        let leaves = |resume: &Resume| resume.pages.main_surface().iter().filter(|node| node.is_leaf()).count();
+        resume.focus_tab(&Tab::from(Page::home()));
+        let before = leaves(&resume);
+        let goals = Page::new("goals");
+        let anchor = "Long Term".to_owned();
+        let scroll = Request::ScrollTo { page: goals.clone(), anchor, placement: Placement::Split };
+        resume.viewer.state.requests.push(scroll);
+        resume.drain_requests();
+        assert_eq!(leaves(&resume), before + 1);
+        assert!(resume.viewer.state.scrolls_to(&goals, "long-term"));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn focusing_an_undocked_page_brings_its_window_to_the_front() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        let help = Tab::from(Page::new("help"));
+        resume.viewer.state.requests.push(Request::Undock(help.clone()));
+        resume.drain_requests();
+        assert!(resume.docked_tab(&help.page).is_none());
+        resume.viewer.state.requests.push(Request::FocusPage(help.page.clone()));
+        resume.drain_requests();
+        assert_eq!(resume.focus_undocked, Some(help));
+    }
+
+    #[test]
+    fn replaced_layouts_keep_the_eject_label() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        let label = |resume: &Resume| resume.pages.translations.tab_context_menu.eject_button.clone();
+        assert_eq!(label(&resume), "Move to new window");
+        for request in [Request::UsePreset(Preset::Recruiter), Request::ResetLayout] {
+            resume.viewer.state.requests.push(request);
+            resume.drain_requests();
+            assert_eq!(label(&resume), "Move to new window");
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn undocked_pages_are_not_recently_closed() {
+        let mut resume = resume(content::DEFAULT_CONTENT);
+        let [help, contact] = ["help", "contact"].map(|id| Tab::from(Page::new(id)));
+        resume.viewer.state.requests = vec![Request::CloseTab(help.clone()), Request::CloseTab(contact.clone())];
+        resume.drain_requests();
+        assert_eq!(resume.recently_closed(), [contact.page.clone(), help.page.clone()]);
+        // Reopened in a window of its own.
+        resume.viewer.state.requests = vec![Request::ReopenClosed, Request::Undock(contact)];
+        resume.drain_requests();
+        assert_eq!(resume.recently_closed(), [help.page]);
+    }
+}
@@ -0,0 +1,440 @@
+//! CommonMark rendering for page content, with `[[target|label]]` internal links.
+
+use eframe::egui;
+use pulldown_cmark::{Event, HeadingLevel, Tag, TagEnd};
+
+use crate::pages::{self, Page};
+use crate::paragraph::RichParagraph;
+use crate::{follow_link, push_wiki_link, Link, State};
+
+/// Part of a text with internal links split out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Piece<'a> {
+    Text(&'a str),
+    /// `[[target]]` or `[[target|label]]`, where the target is a page id or title.
+    WikiLink { target: &'a str, label: &'a str },
+}
+
+/// Splits the `[[target|label]]` links out of `text`. Brackets without a target stay text.
+pub fn split_wiki_links(text: &str) -> Vec<Piece<'_>> {
+    let mut pieces = vec![];
+    // The end of the last link, and where to look for the next one.
+    let (mut done, mut from) = (0, 0);
+    while let Some(start) = text[from..].find("[[").map(|i| from + i) {
+        let Some(len) = text[start + 2..].find("]]") else {
+            break;
+        };
+        let link = &text[start + 2..start + 2 + len];
+        let (target, label) = link.split_once('|').unwrap_or((link, link));
+        from = start + 4 + len;
+        if target.trim().is_empty() {
+            continue;
+        }
+        if start > done {
+            pieces.push(Piece::Text(&text[done..start]));
+        }
+        pieces.push(Piece::WikiLink { target: target.trim(), label });
+        done = from;
+    }
+    if done < text.len() {
+        pieces.push(Piece::Text(&text[done..]));
+    }
+    pieces
+}
+
+/// A run of text inside a paragraph, heading or list item.
+struct Inline {
+    text: egui::RichText,
+    link: Option<InlineLink>,
+}
+
+enum InlineLink {
+    Url(String),
+    /// An unresolved `[[target]]`.
+    Wiki(String),
+}
+
+#[derive(Default)]
+struct Style {
+    emphasis: usize,
+    strong: usize,
+    strikethrough: usize,
+    heading: Option<HeadingLevel>,
+}
+
+impl Style {
+    fn apply(&self, text: egui::RichText) -> egui::RichText {
+        let mut text = match self.heading {
+            Some(HeadingLevel::H1 | HeadingLevel::H2) => text.heading(),
+            Some(_) => text.strong().size(19.0),
+            None => text,
+        };
+        if self.emphasis > 0 {
+            text = text.italics();
+        }
+        if self.strong > 0 {
+            text = text.strong();
+        }
+        if self.strikethrough > 0 {
+            text = text.strikethrough();
+        }
+        text
+    }
+}
+
+struct List {
+    /// The next item number of a numbered list.
+    next: Option<u64>,
+}
+
+#[derive(Default)]
+struct Renderer {
+    /// Text that hasn't been split into inlines yet, since the parser splits `[[` and `]]` apart.
+    pending: String,
+    inlines: Vec<Inline>,
+    style: Style,
+    lists: Vec<List>,
+    /// Marker of the list item whose text hasn't been shown yet.
+    marker: Option<String>,
+    url: Option<String>,
+    /// Alt text of the image being parsed.
+    image: Option<(String, String)>,
+    code_block: Option<String>,
+    /// Plain text of the heading being parsed, which is its anchor.
+    heading: Option<String>,
+    /// The anchor to scroll to on this page, see [`State::scrolls_to`].
+    scroll_to: Option<String>,
+}
+
+impl Renderer {
+    fn text(&mut self, text: &str) {
+        if let Some((_, alt)) = &mut self.image {
+            alt.push_str(text);
+        } else if let Some(code) = &mut self.code_block {
+            code.push_str(text);
+        } else {
+            if let Some(heading) = &mut self.heading {
+                heading.push_str(text);
+            }
+            self.pending.push_str(text);
+        }
+    }
+
+    /// Turns the pending text into inlines with the current style.
+    fn settle(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        for piece in split_wiki_links(&pending) {
+            let (text, link) = match piece {
+                Piece::Text(text) => (text, self.url.clone().map(InlineLink::Url)),
+                Piece::WikiLink { target, label } => (label, Some(InlineLink::Wiki(target.to_owned()))),
+            };
+            self.inlines.push(Inline {
+                text: self.style.apply(egui::RichText::new(text)),
+                link,
+            });
+        }
+    }
+
+    fn code(&mut self, code: &str) {
+        if let Some(heading) = &mut self.heading {
+            heading.push_str(code);
+        }
+        self.inlines.push(Inline {
+            text: self.style.apply(egui::RichText::new(code).code()),
+            link: self.url.clone().map(InlineLink::Url),
+        });
+    }
+
+    /// Shows the pending inlines as one wrapped block, if there are any.
+    fn flush(&mut self, ui: &mut egui::Ui, state: &mut State) -> Option<egui::Response> {
+        let inlines = std::mem::take(&mut self.inlines);
+        let marker = self.marker.take();
+        if inlines.is_empty() && marker.is_none() {
+            return None;
+        }
+        let mut rich = RichParagraph::new();
+        for inline in inlines {
+            match inline.link {
+                Some(InlineLink::Url(url)) => rich.link(inline.text, Link::Url(url)),
+                Some(InlineLink::Wiki(target)) => push_wiki_link(ui, state, &mut rich, &target, inline.text),
+                None => rich.text(inline.text),
+            }
+        }
+        let indent = self.lists.len().saturating_sub(1) as f32 * ui.spacing().indent;
+        let shown = ui.horizontal_top(|ui| {
+            if let Some(marker) = marker {
+                ui.add_space(indent);
+                ui.weak(marker);
+            }
+            rich.show(ui)
+        }).inner;
+        if let Some(link) = shown.inner {
+            follow_link(state, link, &shown.response);
+        }
+        Some(shown.response)
+    }
+
+    fn start(&mut self, ui: &mut egui::Ui, state: &mut State, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush(ui, state);
+                self.style.heading = Some(level);
+                self.heading = Some(String::new());
+            }
+            Tag::List(start) => {
+                self.flush(ui, state);
+                self.lists.push(List { next: start });
+            }
+            Tag::Item => {
+                self.flush(ui, state);
+                let marker = match self.lists.last_mut().and_then(|list| list.next.as_mut()) {
+                    Some(next) => {
+                        *next += 1;
+                        format!("{}. ", *next - 1)
+                    }
+                    None => "◾ ".to_owned(),
+                };
+                self.marker = Some(marker);
+            }
+            Tag::CodeBlock(_) => {
+                self.flush(ui, state);
+                self.code_block = Some(String::new());
+            }
+            Tag::Emphasis => self.style.emphasis += 1,
+            Tag::Strong => self.style.strong += 1,
+            Tag::Strikethrough => self.style.strikethrough += 1,
+            Tag::Link { dest_url, .. } => self.url = Some(dest_url.to_string()),
+            Tag::Image { dest_url, .. } => self.image = Some((dest_url.to_string(), String::new())),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, ui: &mut egui::Ui, state: &mut State, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::Item => {
+                self.flush(ui, state);
+            }
+            TagEnd::Heading(_) => {
+                let shown = self.flush(ui, state);
+                self.style.heading = None;
+                let anchor = self.heading.take().map(|heading| pages::link_key(&heading));
+                if let Some(response) = shown.filter(|_| anchor.is_some() && anchor == self.scroll_to) {
+                    response.scroll_to_me(Some(egui::Align::TOP));
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush(ui, state);
+                self.lists.pop();
+            }
+            TagEnd::CodeBlock => {
+                if let Some(code) = self.code_block.take() {
+                    ui.add(egui::Label::new(egui::RichText::new(code.trim_end()).code()));
+                }
+            }
+            TagEnd::Emphasis => self.style.emphasis = self.style.emphasis.saturating_sub(1),
+            TagEnd::Strong => self.style.strong = self.style.strong.saturating_sub(1),
+            TagEnd::Strikethrough => self.style.strikethrough = self.style.strikethrough.saturating_sub(1),
+            TagEnd::Link => self.url = None,
+            TagEnd::Image => {
+                if let Some((url, alt)) = self.image.take() {
+                    self.flush(ui, state);
+                    let resp = ui.add(egui::Image::new(url).max_width(ui.available_width()).rounding(11.0));
+                    if !alt.is_empty() {
+                        resp.on_hover_text(alt);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renders CommonMark `text` of `page` into `ui`, scrolling to the heading of the anchor
+/// [`crate::Request::ScrollTo`] asked for.
+pub fn markdown_ui(ui: &mut egui::Ui, state: &mut State, page: &Page, text: &str) {
+    let options = pulldown_cmark::Options::ENABLE_STRIKETHROUGH;
+    let mut renderer = Renderer {
+        scroll_to: state.scroll_to.as_ref().filter(|(to, _)| to == page).map(|(_, anchor)| anchor.clone()),
+        ..Default::default()
+    };
+    for event in pulldown_cmark::Parser::new_ext(text, options) {
+        if !matches!(event, Event::Text(_) | Event::SoftBreak | Event::HardBreak) {
+            renderer.settle();
+        }
+        match event {
+            Event::Start(tag) => renderer.start(ui, state, tag),
+            Event::End(tag) => renderer.end(ui, state, tag),
+            Event::Text(text) => renderer.text(&text),
+            Event::Code(code) => renderer.code(&code),
+            Event::SoftBreak => renderer.text(" "),
+            Event::HardBreak => renderer.text("\n"),
+            Event::Rule => {
+                renderer.flush(ui, state);
+                ui.separator();
+            }
+            _ => {}
+        }
+    }
+    renderer.settle();
+    renderer.flush(ui, state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders `text` as the home page in a small scroll area, returning how far it scrolled.
+    fn scrolled(state: &mut State, text: &str) -> f32 {
+        let ctx = egui::Context::default();
+        let mut offset = 0.0;
+        // Scrolling to a heading takes effect in the next frame.
+        for _ in 0..2 {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 300.0))),
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let scroll = egui::ScrollArea::vertical();
+                    offset = scroll.show(ui, |ui| markdown_ui(ui, state, &Page::home(), text)).state.offset.y;
+                });
+            });
+        }
+        offset
+    }
+
+    /// The texts `text` renders to as the home page, where their first rows start and their
+    /// formatted sections.
+    fn render(text: &str) -> Vec<(egui::Pos2, Vec<(String, egui::TextFormat)>)> {
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(600.0, 800.0))),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            let mut state = State::default();
+            egui::CentralPanel::default().show(ctx, |ui| markdown_ui(ui, &mut state, &Page::home(), text));
+        });
+        output.shapes.into_iter()
+            .filter_map(|clipped| match clipped.shape {
+                egui::Shape::Text(shape) => Some(shape),
+                _ => None,
+            })
+            .map(|shape| {
+                let job = &shape.galley.job;
+                let sections = job.sections.iter()
+                    .map(|section| (job.text[section.byte_range.clone()].to_owned(), section.format.clone()))
+                    .collect();
+                (shape.pos + shape.galley.rows[0].rect.min.to_vec2(), sections)
+            })
+            .collect()
+    }
+
+    /// The rendered texts, with the left edge of each.
+    fn texts(text: &str) -> Vec<(f32, String)> {
+        render(text).into_iter()
+            .map(|(pos, sections)| (pos.x, sections.into_iter().map(|(text, _)| text).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn numbers_lists_from_their_start() {
+        let texts = texts("3. a\n1. b\n1. c\n");
+        let markers: Vec<&str> = texts.iter().step_by(2).map(|(_, text)| &text[..]).collect();
+        assert_eq!(markers, ["3. ", "4. ", "5. "]);
+    }
+
+    #[test]
+    fn indents_nested_lists() {
+        let texts = texts("- a\n  1. b\n     - c\n- d");
+        let markers: Vec<&str> = texts.iter().step_by(2).map(|(_, text)| &text[..]).collect();
+        assert_eq!(markers, ["◾ ", "1. ", "◾ ", "◾ "]);
+        let [a, b, c, d] = [0, 2, 4, 6].map(|i| texts[i].0);
+        assert!(a < b && b < c, "each level is indented further");
+        assert_eq!(d, a);
+    }
+
+    #[test]
+    fn shows_code_blocks_in_monospace() {
+        let rendered = render("Before:\n\n```rust\nfn main() {\n    run();\n}\n```\nAfter.");
+        let texts: Vec<&str> = rendered.iter().map(|(_, sections)| &sections[0].0[..]).collect();
+        assert_eq!(texts, ["Before:", "fn main() {\n    run();\n}", "After."]);
+        assert_eq!(rendered[1].1[0].1.font_id.family, egui::FontFamily::Monospace);
+        assert_eq!(rendered[2].1[0].1.font_id.family, egui::FontFamily::Proportional);
+    }
+
+    #[test]
+    fn images_break_paragraphs() {
+        let rendered = render("Before ![the alt text](https://example.com/a.png) after.");
+        let texts: Vec<&str> = rendered.iter().map(|(_, sections)| &sections[0].0[..]).collect();
+        // Without image loaders, egui shows a warning sign for the image.
+        assert_eq!(texts, ["Before ", "⚠", " after."]);
+        let [before, image, after] = [0, 1, 2].map(|i| rendered[i].0.y);
+        assert!(before < image && image < after);
+    }
+
+    #[test]
+    fn nests_emphasis() {
+        let sections: Vec<_> = render("*a **b ~~c~~** d* e").into_iter()
+            .flat_map(|(_, sections)| sections)
+            .collect();
+        let texts: Vec<&str> = sections.iter().map(|(text, _)| &text[..]).collect();
+        assert_eq!(texts, ["a ", "b ", "c", " d", " e"]);
+        let italics: Vec<bool> = sections.iter().map(|(_, format)| format.italics).collect();
+        assert_eq!(italics, [true, true, true, true, false]);
+        let plain = sections[0].1.color;
+        let strong: Vec<bool> = sections.iter().map(|(_, format)| format.color != plain).collect();
+        assert_eq!(strong, [false, true, true, false, false]);
+        let struck: Vec<bool> = sections.iter().map(|(_, format)| format.strikethrough.width > 0.0).collect();
+        assert_eq!(struck, [false, false, true, false, false]);
+    }
+
+    #[test]
+    fn scrolls_to_headings() {
+        let text = format!("{}## The *Deep* End\n\nDone.", "Filler.\n\n".repeat(50));
+        let mut state = State::default();
+        assert_eq!(scrolled(&mut state, &text), 0.0);
+        state.scroll_to = Some((Page::new("help"), "the-deep-end".to_owned()));
+        assert_eq!(scrolled(&mut state, &text), 0.0);
+        state.scroll_to = Some((Page::home(), "the-deep-end".to_owned()));
+        assert!(scrolled(&mut state, &text) > 0.0);
+    }
+
+    #[test]
+    fn splits_links() {
+        assert_eq!(split_wiki_links("see [[help]]."), [
+            Piece::Text("see "),
+            Piece::WikiLink { target: "help", label: "help" },
+            Piece::Text("."),
+        ]);
+        assert_eq!(split_wiki_links("[[ Work History |my jobs]]"), [
+            Piece::WikiLink { target: "Work History", label: "my jobs" },
+        ]);
+    }
+
+    #[test]
+    fn splits_adjacent_links() {
+        assert_eq!(split_wiki_links("[[a]][[b|c]]"), [
+            Piece::WikiLink { target: "a", label: "a" },
+            Piece::WikiLink { target: "b", label: "c" },
+        ]);
+    }
+
+    #[test]
+    fn keeps_anchors_in_targets() {
+        assert_eq!(split_wiki_links("[[goals#Quick List]]"), [
+            Piece::WikiLink { target: "goals#Quick List", label: "goals#Quick List" },
+        ]);
+    }
+
+    #[test]
+    fn leaves_broken_links_as_text() {
+        assert_eq!(split_wiki_links("[[a] and [[b"), [Piece::Text("[[a] and [[b")]);
+        assert_eq!(split_wiki_links("[[]] or [[|label]]"), [Piece::Text("[[]] or [[|label]]")]);
+        assert_eq!(split_wiki_links("[[]] then [[a]]"), [
+            Piece::Text("[[]] then "),
+            Piece::WikiLink { target: "a", label: "a" },
+        ]);
+        assert_eq!(split_wiki_links(""), []);
+    }
+}
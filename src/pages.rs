@@ -0,0 +1,199 @@
+//! Runtime page registry.
+
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{page_ui, Placement, Request, State};
+
+/// Identifies a page in the [`Registry`].
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(transparent)]
+pub struct Page(String);
+
+impl Page {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn home() -> Self {
+        Self::new("home")
+    }
+
+    /// The page of the project with the given id.
+    pub fn project(id: &str) -> Self {
+        Self(format!("portfolio/{id}"))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    pub fn project_id(&self) -> Option<&str> {
+        self.0.strip_prefix("portfolio/")
+    }
+}
+
+/// An open tab. A page can be open in several tabs, each with its own scroll position and other UI
+/// state, told apart by their instance number.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Tab {
+    pub page: Page,
+    /// 0 for the first tab of the page, counting up for duplicates.
+    #[serde(default)]
+    pub instance: u32,
+}
+
+impl Tab {
+    /// Names the UI state of the tab, which is the page id for the first instance.
+    pub fn name(&self) -> String {
+        match self.instance {
+            0 => self.page.id().to_owned(),
+            n => format!("{}~{n}", self.page.id()),
+        }
+    }
+}
+
+impl From<Page> for Tab {
+    fn from(page: Page) -> Self {
+        Self { page, instance: 0 }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// A page that can be shown in a tab.
+pub trait PageEntry {
+    fn title(&self) -> String;
+
+    fn header(&self, ui: &mut egui::Ui, state: &mut State) {
+        ui.visuals_mut().button_frame = false;
+        if let Some(placement) = Placement::of_click(&ui.button("🏠")) {
+            state.requests.push(Request::OpenPage(Page::home(), placement));
+        }
+        ui.heading(self.title());
+    }
+
+    fn body(&self, ui: &mut egui::Ui, state: &mut State);
+
+    /// Targets of the internal links in the body, as page ids or titles.
+    fn links(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Overrides the "Related:" footer, which otherwise lists the pages linked to and from this one.
+    fn related(&self) -> Option<Vec<Page>> {
+        None
+    }
+}
+
+/// All pages that can be opened, keyed by page id.
+#[derive(Default)]
+pub struct Registry {
+    entries: BTreeMap<Page, Box<dyn PageEntry>>,
+}
+
+impl Registry {
+    /// Adds a page, replacing any page previously registered under the same id.
+    pub fn register(&mut self, page: Page, entry: impl PageEntry + 'static) {
+        self.entries.insert(page, Box::new(entry));
+    }
+
+    pub fn get(&self, page: &Page) -> Option<&dyn PageEntry> {
+        self.entries.get(page).map(|entry| entry.as_ref())
+    }
+
+    pub fn contains(&self, page: &Page) -> bool {
+        self.entries.contains_key(page)
+    }
+
+    pub fn pages(&self) -> impl Iterator<Item = &Page> {
+        self.entries.keys()
+    }
+
+    /// Maps the id and title of every page to the page, for resolving `[[target]]` links.
+    pub fn link_targets(&self) -> BTreeMap<String, Page> {
+        let titles = self.entries.iter().map(|(page, entry)| (link_key(&entry.title()), page.clone()));
+        let ids = self.entries.keys().map(|page| (link_key(page.id()), page.clone()));
+        // Ids come last so they win over a page titled like another page's id.
+        titles.chain(ids).collect()
+    }
+
+    pub fn title(&self, page: &Page) -> String {
+        self.get(page).map_or_else(|| page.id().to_owned(), |entry| entry.title())
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui, state: &mut State, tab: &Tab) {
+        let page = &tab.page;
+        let Some(entry) = self.get(page) else {
+            ui.colored_label(egui::Color32::LIGHT_RED, format!("Unknown page `{}`", page.id()));
+            return;
+        };
+        let related = entry.related().unwrap_or_else(|| state.graph.related(page));
+        page_ui(ui, state, &tab.name(),
+                |ui, state| entry.body(ui, state),
+                |ui, state| entry.header(ui, state),
+                |ui, state| {
+                    for related in related {
+                        if let Some(placement) = Placement::of_click(&ui.link(self.title(&related))) {
+                            state.requests.push(Request::OpenPage(related, placement));
+                        }
+                    }
+                },
+        );
+        // Anchors that match no section are dropped once the page was shown.
+        if state.scroll_to.as_ref().is_some_and(|(to, _)| to == page) {
+            state.scroll_to = None;
+        }
+    }
+}
+
+/// Normalizes a link target, so `[[Work History]]` finds the `work-history` page and
+/// `[[goals#Quick List]]` the `goals` page. Also turns section headings into anchors.
+pub fn link_key(target: &str) -> String {
+    let page = target.split_once('#').map_or(target, |(page, _)| page);
+    page.trim().to_lowercase().replace(' ', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A page added in code.
+    struct Guestbook(&'static str);
+
+    impl PageEntry for Guestbook {
+        fn title(&self) -> String {
+            self.0.to_owned()
+        }
+
+        fn body(&self, ui: &mut egui::Ui, _: &mut State) {
+            ui.label("Thanks for visiting!");
+        }
+    }
+
+    #[test]
+    fn registered_pages_replace_earlier_ones() {
+        let mut pages = Registry::default();
+        let guestbook = Page::new("guestbook");
+        assert!(!pages.contains(&guestbook));
+        assert_eq!(pages.title(&guestbook), "guestbook", "unknown pages are titled by their id");
+
+        pages.register(guestbook.clone(), Guestbook("Guestbook"));
+        pages.register(Page::home(), Guestbook("Home"));
+        pages.register(guestbook.clone(), Guestbook("Visitors"));
+        assert!(pages.contains(&guestbook));
+        assert_eq!(pages.title(&guestbook), "Visitors");
+        assert_eq!(pages.pages().collect::<Vec<_>>(), [&guestbook, &Page::home()]);
+    }
+
+    #[test]
+    fn link_keys() {
+        assert_eq!(link_key("work-history"), "work-history");
+        assert_eq!(link_key(" Work History "), "work-history");
+        assert_eq!(link_key("goals#Quick List"), "goals");
+        assert_eq!(link_key("Quick List"), "quick-list");
+        assert_eq!(link_key("#Quick List"), "");
+    }
+}
@@ -0,0 +1,150 @@
+//! A paragraph widget mixing styled text and links in one flowing, word-wrapped text.
+
+use std::sync::Arc;
+
+use eframe::egui;
+
+struct Run<L> {
+    text: egui::RichText,
+    link: Option<L>,
+    hover: Option<String>,
+}
+
+/// Text laid out as a single [`egui::text::LayoutJob`], so it wraps like one label even across
+/// link boundaries.
+pub struct RichParagraph<L> {
+    runs: Vec<Run<L>>,
+}
+
+impl<L: Clone> RichParagraph<L> {
+    pub fn new() -> Self {
+        Self { runs: vec![] }
+    }
+
+    pub fn text(&mut self, text: impl Into<egui::RichText>) {
+        self.runs.push(Run { text: text.into(), link: None, hover: None });
+    }
+
+    /// Text with a tooltip, such as a broken link.
+    pub fn annotated(&mut self, text: impl Into<egui::RichText>, hover: impl Into<String>) {
+        self.runs.push(Run { text: text.into(), link: None, hover: Some(hover.into()) });
+    }
+
+    pub fn link(&mut self, text: impl Into<egui::RichText>, link: L) {
+        self.runs.push(Run { text: text.into(), link: Some(link), hover: None });
+    }
+
+    /// Lays out all runs as one job, underlining the `hovered` run.
+    fn layout(&self, ui: &egui::Ui, max_width: f32, hovered: Option<usize>) -> Arc<egui::Galley> {
+        let mut job = egui::text::LayoutJob {
+            wrap: egui::text::TextWrapping {
+                max_width,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for (i, run) in self.runs.iter().enumerate() {
+            let mut text = run.text.clone();
+            if run.link.is_some() {
+                text = text.color(ui.visuals().hyperlink_color);
+                if hovered == Some(i) {
+                    text = text.underline();
+                }
+            }
+            text.append_to(&mut job, ui.style(), egui::FontSelection::Default, egui::Align::Min);
+        }
+        ui.fonts(|fonts| fonts.layout_job(job))
+    }
+
+    /// Shows the paragraph, returning the link that was clicked or middle-clicked, if any.
+    pub fn show(self, ui: &mut egui::Ui) -> egui::InnerResponse<Option<L>> {
+        let max_width = ui.available_width();
+        let galley = self.layout(ui, max_width, None);
+        let (rect, response) = ui.allocate_exact_size(galley.size(), egui::Sense::click());
+        let hovered = response.hover_pos().and_then(|pos| {
+            let pos = pos - rect.min.to_vec2();
+            galley.rows.iter()
+                .flat_map(|row| &row.glyphs)
+                .find(|glyph| glyph.logical_rect().contains(pos))
+                .map(|glyph| glyph.section_index as usize)
+        });
+        let hovered_link = hovered.filter(|&i| self.runs[i].link.is_some());
+        let galley = match hovered_link {
+            Some(_) => self.layout(ui, max_width, hovered_link),
+            None => galley,
+        };
+        ui.painter().galley(rect.min, galley, ui.visuals().text_color());
+
+        let mut response = response;
+        if hovered_link.is_some() {
+            response = response.on_hover_cursor(egui::CursorIcon::PointingHand);
+        }
+        if let Some(hover) = hovered.and_then(|i| self.runs[i].hover.as_ref()) {
+            response = response.on_hover_text_at_pointer(hover);
+        }
+        let clicked = hovered_link
+            .filter(|_| response.clicked() || response.middle_clicked())
+            .and_then(|i| self.runs[i].link.clone());
+        egui::InnerResponse::new(clicked, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph() -> RichParagraph<&'static str> {
+        let mut paragraph = RichParagraph::new();
+        paragraph.text("Some text before ");
+        paragraph.link("the first link", "first");
+        paragraph.annotated(" and a note,", "a note");
+        paragraph.text(" then enough text to wrap the paragraph onto more rows before ");
+        paragraph.link("the second link", "second");
+        paragraph
+    }
+
+    /// Clicks the paragraph at the first glyph of `run` in a narrow window, returning the link that
+    /// was clicked.
+    fn click(run: usize) -> Option<&'static str> {
+        let ctx = egui::Context::default();
+        let input = |events| egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(240.0, 400.0))),
+            events,
+            ..Default::default()
+        };
+        let button = |pos, pressed| egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        };
+        let mut pos = egui::Pos2::ZERO;
+        let _ = ctx.run(input(vec![]), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let paragraph = paragraph();
+                let galley = paragraph.layout(ui, ui.available_width(), None);
+                assert!(galley.rows.len() > 2, "the paragraph wraps");
+                let glyph = galley.rows.iter()
+                    .flat_map(|row| &row.glyphs)
+                    .find(|glyph| glyph.section_index as usize == run)
+                    .expect("the run has glyphs");
+                pos = paragraph.show(ui).response.rect.min + glyph.logical_rect().center().to_vec2();
+            });
+        });
+        let mut clicked = None;
+        for events in [vec![egui::Event::PointerMoved(pos), button(pos, true)], vec![button(pos, false)]] {
+            let _ = ctx.run(input(events), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| clicked = paragraph().show(ui).inner);
+            });
+        }
+        clicked
+    }
+
+    #[test]
+    fn clicks_hit_the_run_under_the_pointer() {
+        assert_eq!(click(0), None);
+        assert_eq!(click(1), Some("first"));
+        assert_eq!(click(2), None, "annotated text isn't a link");
+        assert_eq!(click(4), Some("second"));
+    }
+}
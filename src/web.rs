@@ -0,0 +1,97 @@
+//! Browser integration of the web build.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use eframe::egui;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::pages::Page;
+
+/// The URL fragment, including the leading `#`, or an empty string.
+pub fn location_hash() -> String {
+    web_sys::window()
+        .and_then(|window| window.location().hash().ok())
+        .unwrap_or_default()
+}
+
+/// The URL of the current page without its fragment.
+pub fn location_without_hash() -> String {
+    let href = web_sys::window()
+        .and_then(|window| window.location().href().ok())
+        .unwrap_or_default();
+    match href.split_once('#') {
+        Some((href, _)) => href.to_owned(),
+        None => href,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+/// Mirrors opened pages in the URL as `#/<page id>`, so the browser's back and forward buttons and
+/// shared links can open pages.
+pub struct BrowserHistory {
+    /// Page of the last `popstate` event, which the app hasn't opened yet.
+    popped: Rc<RefCell<Option<Page>>>,
+    _listener: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl BrowserHistory {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let popped = Rc::new(RefCell::new(None));
+        let listener = Closure::<dyn FnMut(web_sys::Event)>::new({
+            let popped = popped.clone();
+            let ctx = ctx.clone();
+            move |_| {
+                if let Some(page) = page_from_hash(&location_hash()) {
+                    *popped.borrow_mut() = Some(page);
+                    ctx.request_repaint();
+                }
+            }
+        });
+        if let Some(window) = web_sys::window() {
+            window
+                .add_event_listener_with_callback("popstate", listener.as_ref().unchecked_ref())
+                .ok();
+        }
+        Self { popped, _listener: listener }
+    }
+
+    /// The page the browser navigated to with back or forward.
+    pub fn take_popped(&self) -> Option<Page> {
+        self.popped.borrow_mut().take()
+    }
+
+    /// Adds a history entry for `page`, unless the URL already points to it.
+    pub fn push(&self, page: &Page) {
+        self.update(page, true);
+    }
+
+    /// Points the current history entry to `page`, for focus changes that aren't navigation.
+    pub fn replace(&self, page: &Page) {
+        self.update(page, false);
+    }
+
+    fn update(&self, page: &Page, push: bool) {
+        let url = format!("#/{}", page.id());
+        if location_hash() == url {
+            return;
+        }
+        let Some(history) = web_sys::window().and_then(|window| window.history().ok()) else {
+            return;
+        };
+        if push {
+            history.push_state_with_url(&JsValue::NULL, "", Some(&url)).ok();
+        } else {
+            history.replace_state_with_url(&JsValue::NULL, "", Some(&url)).ok();
+        }
+    }
+}
+
+/// The page of a `#/<page id>` URL fragment.
+pub fn page_from_hash(hash: &str) -> Option<Page> {
+    hash.strip_prefix("#/")
+        .filter(|id| !id.is_empty())
+        .map(Page::new)
+}